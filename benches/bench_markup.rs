@@ -1,6 +1,8 @@
 use std::hint::black_box;
 use criterion::{Criterion, criterion_group, criterion_main};
-use markup::Markup;
+#[cfg(feature = "arena")]
+use markup::arena::{Arena, IntoArena};
+use markup::{IntoMarkup, Markup};
 
 pub fn bench_markup(c: &mut Criterion) {
     c.bench_function("markup", |b| {
@@ -133,5 +135,96 @@ pub fn bench_markup_escape(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_markup, bench_markup_escape);
+pub fn bench_escape_long_clean(c: &mut Criterion) {
+    let text = "The quick brown fox jumps over the lazy dog. ".repeat(2_000);
+    let markup = Markup::element("p").child(text).into_markup();
+
+    c.bench_function("escape_long_clean", |b| b.iter(|| black_box(markup.render())));
+}
+
+pub fn bench_escape_long_dense(c: &mut Criterion) {
+    let text = "<a href=\"x\">'&'</a> ".repeat(2_000);
+    let markup = Markup::element("p").child(text).into_markup();
+
+    c.bench_function("escape_long_dense", |b| b.iter(|| black_box(markup.render())));
+}
+
+fn large_table() -> Markup {
+    Markup::element("table")
+        .child(
+            (0..2_000)
+                .map(|i| {
+                    Markup::element("tr")
+                        .child(Markup::element("td").child(format!("{i}")))
+                        .child(Markup::element("td").attr("class", "name").child("Row & name"))
+                })
+                .into_markup(),
+        )
+        .into_markup()
+}
+
+pub fn bench_render_large(c: &mut Criterion) {
+    let markup = large_table();
+
+    c.bench_function("render_large", |b| b.iter(|| black_box(markup.render())));
+}
+
+pub fn bench_render_into_large(c: &mut Criterion) {
+    let markup = large_table();
+    let mut buffer = String::new();
+
+    c.bench_function("render_into_large", |b| {
+        b.iter(|| {
+            buffer.clear();
+            markup.render_into(&mut buffer);
+            black_box(buffer.len())
+        })
+    });
+}
+
+pub fn bench_build_render_vec(c: &mut Criterion) {
+    c.bench_function("build_render_large_vec", |b| b.iter(|| black_box(large_table().render())));
+}
+
+#[cfg(feature = "arena")]
+pub fn bench_build_render_arena(c: &mut Criterion) {
+    let mut arena = Arena::new();
+
+    c.bench_function("build_render_large_arena", |b| {
+        b.iter(|| {
+            arena.reset();
+            let markup = arena
+                .element("table")
+                .child(
+                    (0..2_000)
+                        .map(|i| {
+                            arena
+                                .element("tr")
+                                .child(arena.element("td").child(format!("{i}")))
+                                .child(arena.element("td").attr("class", "name").child("Row & name"))
+                        })
+                        .into_arena(&arena),
+                )
+                .into_markup();
+            black_box(markup.render())
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_markup,
+    bench_markup_escape,
+    bench_escape_long_clean,
+    bench_escape_long_dense,
+    bench_render_large,
+    bench_render_into_large,
+    bench_build_render_vec
+);
+#[cfg(feature = "arena")]
+criterion_group!(arena_benches, bench_build_render_arena);
+
+#[cfg(not(feature = "arena"))]
 criterion_main!(benches);
+#[cfg(feature = "arena")]
+criterion_main!(benches, arena_benches);
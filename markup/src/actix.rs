@@ -0,0 +1,110 @@
+//! `Responder` for trees and tags, behind the `actix` feature.
+
+use crate::chunks::{CHUNK_SIZE, STREAM_THRESHOLD, TEXT_HTML};
+use crate::{IntoMarkup, Markup, RegularTag, RenderOptions, SelfClosingTag};
+use actix_web::body::BoxBody;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{CONTENT_TYPE, TryIntoHeaderPair};
+use actix_web::web::Bytes;
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, Responder};
+use std::fmt;
+
+/// An HTML response with its own status and headers.
+///
+/// Like the plain [`Responder`] impls, the body is streamed when the page's
+/// size hint reaches 64 KiB or it has [`Suspense`](crate::Suspense)
+/// boundaries, unless [`Html::streaming`] says otherwise. A page that fails
+/// to render up front is answered with a 500.
+pub struct Html {
+    markup: Markup,
+    response: HttpResponseBuilder,
+    streaming: Option<bool>,
+}
+
+impl Html {
+    pub fn new<M: IntoMarkup>(markup: M) -> Self {
+        let mut response = HttpResponse::Ok();
+        response.insert_header((CONTENT_TYPE, TEXT_HTML));
+        Html {
+            markup: markup.into_markup(),
+            response,
+            streaming: None,
+        }
+    }
+
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.response.status(status);
+        self
+    }
+
+    /// Sets a header, replacing any previous value, including the default
+    /// `Content-Type`. An invalid header turns the response into an error.
+    pub fn header(mut self, header: impl TryIntoHeaderPair) -> Self {
+        self.response.insert_header(header);
+        self
+    }
+
+    /// Forces the body to be streamed in chunks, or rendered up front. A page
+    /// rendered up front shows the fallbacks of its suspense boundaries.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = Some(streaming);
+        self
+    }
+}
+
+impl fmt::Debug for Html {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Html")
+            .field("markup", &self.markup)
+            .field("streaming", &self.streaming)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Responder for Html {
+    type Body = BoxBody;
+
+    fn respond_to(mut self, _: &HttpRequest) -> HttpResponse {
+        let streaming = self.streaming.unwrap_or_else(|| {
+            self.markup.size_hint() >= STREAM_THRESHOLD || self.markup.suspends()
+        });
+        if !streaming {
+            return match self.markup.render_with(&RenderOptions::default()) {
+                Ok(html) => self.response.body(html),
+                Err(error) => HttpResponse::InternalServerError().body(error.to_string()),
+            };
+        }
+
+        let mut stream = self.markup.into_stream(CHUNK_SIZE);
+        self.response
+            .streaming(futures_util::stream::poll_fn(move |cx| {
+                stream
+                    .poll_chunk(cx)
+                    .map(|chunk| chunk.map(|chunk| chunk.map(Bytes::from)))
+            }))
+    }
+}
+
+impl Responder for Markup {
+    type Body = BoxBody;
+
+    fn respond_to(self, request: &HttpRequest) -> HttpResponse {
+        Html::new(self).respond_to(request)
+    }
+}
+
+impl Responder for RegularTag {
+    type Body = BoxBody;
+
+    fn respond_to(self, request: &HttpRequest) -> HttpResponse {
+        Html::new(self).respond_to(request)
+    }
+}
+
+impl Responder for SelfClosingTag {
+    type Body = BoxBody;
+
+    fn respond_to(self, request: &HttpRequest) -> HttpResponse {
+        Html::new(self).respond_to(request)
+    }
+}
@@ -0,0 +1,379 @@
+//! An arena-backed alternative to [`crate::Markup`].
+//!
+//! Nodes, attribute lists and child lists all live in one bump allocator, so
+//! building a page costs a handful of large allocations instead of one per
+//! element. Trees borrow the [`Arena`], which is reset between requests.
+
+use crate::render::{self, Node, Value, View};
+use crate::{RenderError, RenderOptions, attrs};
+use bumpalo::Bump;
+use bumpalo::collections::Vec as BumpVec;
+
+#[derive(Debug, Default)]
+pub struct Arena {
+    bump: Bump,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Markup<'a> {
+    Text(&'a str),
+    Raw(&'a str),
+    Fragment(&'a [Markup<'a>]),
+    RegularTag(&'a Element<'a>),
+    SelfClosingTag(&'a VoidElement<'a>),
+    None,
+}
+
+#[derive(Debug)]
+pub struct Element<'a> {
+    pub tag: &'static str,
+    pub attributes: &'a [Attribute<'a>],
+    pub children: &'a [Markup<'a>],
+}
+
+#[derive(Debug)]
+pub struct VoidElement<'a> {
+    pub tag: &'static str,
+    pub attributes: &'a [Attribute<'a>],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Attribute<'a> {
+    pub name: &'static str,
+    pub value: AttrValue<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AttrValue<'a> {
+    Str(&'a str),
+    Bool(bool),
+}
+
+/// Builder for an element whose attributes and children are allocated in
+/// the arena.
+#[derive(Debug)]
+pub struct RegularTag<'a> {
+    arena: &'a Arena,
+    tag: &'static str,
+    attributes: BumpVec<'a, Attribute<'a>>,
+    children: BumpVec<'a, Markup<'a>>,
+}
+
+#[derive(Debug)]
+pub struct SelfClosingTag<'a> {
+    arena: &'a Arena,
+    tag: &'static str,
+    attributes: BumpVec<'a, Attribute<'a>>,
+}
+
+pub trait IntoArena<'a> {
+    fn into_arena(self, arena: &'a Arena) -> Markup<'a>;
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    pub fn with_capacity(bytes: usize) -> Self {
+        Arena {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Frees every tree built in this arena while keeping its largest chunk
+    /// of memory for the next request.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    pub fn element(&self, tag: &'static str) -> RegularTag<'_> {
+        RegularTag {
+            arena: self,
+            tag,
+            attributes: BumpVec::new_in(&self.bump),
+            children: BumpVec::new_in(&self.bump),
+        }
+    }
+
+    pub fn self_element(&self, tag: &'static str) -> SelfClosingTag<'_> {
+        SelfClosingTag {
+            arena: self,
+            tag,
+            attributes: BumpVec::new_in(&self.bump),
+        }
+    }
+
+    /// Copies `content` into the arena as a text node.
+    pub fn text(&self, content: &str) -> Markup<'_> {
+        Markup::Text(self.bump.alloc_str(content))
+    }
+
+    pub fn raw(&self, content: &'static str) -> Markup<'_> {
+        Markup::Raw(content)
+    }
+
+    fn value(&self, value: crate::AttrValue) -> AttrValue<'_> {
+        match value {
+            crate::AttrValue::Static(value) => AttrValue::Str(value),
+            crate::AttrValue::Owned(value) => AttrValue::Str(self.bump.alloc_str(&value)),
+            crate::AttrValue::Bool(value) => AttrValue::Bool(value),
+        }
+    }
+
+    fn fragment<'a, I, T>(&'a self, items: I) -> Markup<'a>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoArena<'a>,
+    {
+        let mut children = BumpVec::new_in(&self.bump);
+        children.extend(items.into_iter().map(|item| item.into_arena(self)));
+        Markup::Fragment(children.into_bump_slice())
+    }
+}
+
+impl<'a> Markup<'a> {
+    pub fn render(&self) -> String {
+        self.render_with(&RenderOptions::default())
+            .expect("rendering without a CSP nonce cannot fail")
+    }
+
+    pub fn render_into(&self, buffer: &mut String) {
+        self.render_into_with(buffer, &RenderOptions::default())
+            .expect("rendering without a CSP nonce cannot fail")
+    }
+
+    pub fn render_with(&self, options: &RenderOptions) -> Result<String, RenderError> {
+        let mut buffer = String::with_capacity(render::size_hint(self, options));
+        self.render_into_with(&mut buffer, options)?;
+        Ok(buffer)
+    }
+
+    pub fn render_into_with(
+        &self,
+        buffer: &mut String,
+        options: &RenderOptions,
+    ) -> Result<(), RenderError> {
+        render::render_into(self, buffer, options)
+    }
+
+    pub fn size_hint(&self) -> usize {
+        render::size_hint(self, &RenderOptions::default())
+    }
+}
+
+/// Sets an attribute with the same merging rules as [`crate::RegularTag::attr`].
+fn set<'a>(
+    arena: &'a Arena,
+    attributes: &mut BumpVec<'a, Attribute<'a>>,
+    name: &'static str,
+    value: crate::AttrValue,
+) {
+    let Some(existing) = attributes
+        .iter_mut()
+        .find(|attr| attr.name.eq_ignore_ascii_case(name))
+    else {
+        attributes.push(Attribute {
+            name,
+            value: arena.value(value),
+        });
+        return;
+    };
+
+    match (existing.value, value.as_str()) {
+        (AttrValue::Str(old), Some(new)) if name.eq_ignore_ascii_case("class") => {
+            if let Some(merged) = attrs::merge_classes(old, new) {
+                existing.value = AttrValue::Str(arena.bump.alloc_str(&merged));
+            }
+        }
+        (AttrValue::Str(old), Some(new)) if name.eq_ignore_ascii_case("style") => {
+            let merged = attrs::merge_styles(old, new);
+            existing.value = AttrValue::Str(arena.bump.alloc_str(&merged));
+        }
+        _ => existing.value = arena.value(value),
+    }
+}
+
+impl<'a> RegularTag<'a> {
+    /// Sets an attribute, merging duplicates like [`crate::RegularTag::attr`].
+    pub fn attr<V: Into<crate::AttrValue>>(mut self, name: &'static str, value: V) -> Self {
+        set(self.arena, &mut self.attributes, name, value.into());
+        self
+    }
+
+    pub fn id<V: Into<crate::AttrValue>>(self, id: V) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn class<V: Into<crate::AttrValue>>(self, class: V) -> Self {
+        self.attr("class", class)
+    }
+
+    pub fn classes<I, V>(self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<crate::AttrValue>,
+    {
+        classes
+            .into_iter()
+            .fold(self, |tag, class| tag.class(class))
+    }
+
+    pub fn class_if<V: Into<crate::AttrValue>>(self, condition: bool, class: V) -> Self {
+        if condition { self.class(class) } else { self }
+    }
+
+    pub fn style<V: Into<crate::AttrValue>>(self, style: V) -> Self {
+        self.attr("style", style)
+    }
+
+    pub fn child<C: IntoArena<'a>>(mut self, child: C) -> Self {
+        let child = child.into_arena(self.arena);
+        self.children.push(child);
+        self
+    }
+
+    pub fn into_markup(self) -> Markup<'a> {
+        Markup::RegularTag(self.arena.bump.alloc(Element {
+            tag: self.tag,
+            attributes: self.attributes.into_bump_slice(),
+            children: self.children.into_bump_slice(),
+        }))
+    }
+}
+
+impl<'a> SelfClosingTag<'a> {
+    /// Sets an attribute, merging duplicates like [`crate::RegularTag::attr`].
+    pub fn attr<V: Into<crate::AttrValue>>(mut self, name: &'static str, value: V) -> Self {
+        set(self.arena, &mut self.attributes, name, value.into());
+        self
+    }
+
+    pub fn id<V: Into<crate::AttrValue>>(self, id: V) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn class<V: Into<crate::AttrValue>>(self, class: V) -> Self {
+        self.attr("class", class)
+    }
+
+    pub fn classes<I, V>(self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<crate::AttrValue>,
+    {
+        classes
+            .into_iter()
+            .fold(self, |tag, class| tag.class(class))
+    }
+
+    pub fn class_if<V: Into<crate::AttrValue>>(self, condition: bool, class: V) -> Self {
+        if condition { self.class(class) } else { self }
+    }
+
+    pub fn style<V: Into<crate::AttrValue>>(self, style: V) -> Self {
+        self.attr("style", style)
+    }
+
+    pub fn into_markup(self) -> Markup<'a> {
+        Markup::SelfClosingTag(self.arena.bump.alloc(VoidElement {
+            tag: self.tag,
+            attributes: self.attributes.into_bump_slice(),
+        }))
+    }
+}
+
+impl<'a> IntoArena<'a> for Markup<'a> {
+    fn into_arena(self, _: &'a Arena) -> Markup<'a> {
+        self
+    }
+}
+
+impl<'a> IntoArena<'a> for &'a str {
+    fn into_arena(self, _: &'a Arena) -> Markup<'a> {
+        Markup::Text(self)
+    }
+}
+
+impl<'a> IntoArena<'a> for String {
+    fn into_arena(self, arena: &'a Arena) -> Markup<'a> {
+        arena.text(&self)
+    }
+}
+
+impl<'a> IntoArena<'a> for RegularTag<'a> {
+    fn into_arena(self, _: &'a Arena) -> Markup<'a> {
+        self.into_markup()
+    }
+}
+
+impl<'a> IntoArena<'a> for SelfClosingTag<'a> {
+    fn into_arena(self, _: &'a Arena) -> Markup<'a> {
+        self.into_markup()
+    }
+}
+
+impl<'a, T: IntoArena<'a>> IntoArena<'a> for Option<T> {
+    fn into_arena(self, arena: &'a Arena) -> Markup<'a> {
+        match self {
+            Some(value) => value.into_arena(arena),
+            None => Markup::None,
+        }
+    }
+}
+
+impl<'a, T: IntoArena<'a>> IntoArena<'a> for Vec<T> {
+    fn into_arena(self, arena: &'a Arena) -> Markup<'a> {
+        arena.fragment(self)
+    }
+}
+
+impl<'a, I, F, T> IntoArena<'a> for std::iter::Map<I, F>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> T,
+    T: IntoArena<'a>,
+{
+    fn into_arena(self, arena: &'a Arena) -> Markup<'a> {
+        arena.fragment(self)
+    }
+}
+
+impl<'a> Node for Markup<'a> {
+    type Attr = Attribute<'a>;
+
+    fn view(&self) -> View<'_, Self, Attribute<'a>> {
+        match *self {
+            Markup::Text(content) => View::Text(content),
+            Markup::Raw(content) => View::Raw(content),
+            Markup::Fragment(children) => View::Fragment(children),
+            Markup::RegularTag(element) => View::Element {
+                tag: element.tag,
+                attributes: element.attributes,
+                children: element.children,
+            },
+            Markup::SelfClosingTag(element) => View::Void {
+                tag: element.tag,
+                attributes: element.attributes,
+            },
+            Markup::None => View::None,
+        }
+    }
+}
+
+impl render::Attr for Attribute<'_> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn value(&self) -> Value<'_> {
+        match self.value {
+            AttrValue::Str(value) => Value::Str(value),
+            AttrValue::Bool(value) => Value::Bool(value),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{AttrValue, Attribute};
+use std::borrow::Cow;
+
+/// Sets an attribute, merging it with any existing attribute of the same name.
+///
+/// `class` tokens are unioned, `style` declarations are merged property by
+/// property, and any other duplicate is replaced in place.
+pub(crate) fn set(attributes: &mut Vec<Attribute>, name: &'static str, value: AttrValue) {
+    let Some(existing) = attributes
+        .iter_mut()
+        .find(|attr| attr.name.eq_ignore_ascii_case(name))
+    else {
+        attributes.push(Attribute {
+            name: Cow::Borrowed(name),
+            value,
+        });
+        return;
+    };
+
+    match (existing.value.as_str(), value.as_str()) {
+        (Some(old), Some(new)) if name.eq_ignore_ascii_case("class") => {
+            if let Some(merged) = merge_classes(old, new) {
+                existing.value = AttrValue::Owned(merged);
+            }
+        }
+        (Some(old), Some(new)) if name.eq_ignore_ascii_case("style") => {
+            existing.value = AttrValue::Owned(merge_styles(old, new));
+        }
+        _ => existing.value = value,
+    }
+}
+
+/// Appends the tokens of `new` that are not already in `old`.
+/// Returns `None` when nothing needs to change.
+pub(crate) fn merge_classes(old: &str, new: &str) -> Option<String> {
+    let mut merged: Option<String> = None;
+
+    for token in new.split_whitespace() {
+        let current = merged.as_deref().unwrap_or(old);
+        if current.split_whitespace().any(|t| t == token) {
+            continue;
+        }
+
+        let buffer =
+            merged.get_or_insert_with(|| old.split_whitespace().collect::<Vec<_>>().join(" "));
+        if !buffer.is_empty() {
+            buffer.push(' ');
+        }
+        buffer.push_str(token);
+    }
+
+    merged
+}
+
+/// Merges two declaration lists; later properties override earlier ones
+/// while keeping their original position.
+pub(crate) fn merge_styles(old: &str, new: &str) -> String {
+    let mut declarations: Vec<(&str, &str)> = Vec::new();
+
+    for (property, value) in declarations_of(old).chain(declarations_of(new)) {
+        match declarations
+            .iter_mut()
+            .find(|(p, _)| p.eq_ignore_ascii_case(property))
+        {
+            Some(existing) => existing.1 = value,
+            None => declarations.push((property, value)),
+        }
+    }
+
+    let mut output = String::with_capacity(old.len() + new.len() + 2);
+    for (i, (property, value)) in declarations.iter().enumerate() {
+        if i > 0 {
+            output.push_str("; ");
+        }
+        output.push_str(property);
+        output.push_str(": ");
+        output.push_str(value);
+    }
+    output
+}
+
+/// Splits a declaration list into `(property, value)` pairs, ignoring `;`
+/// inside quotes and parentheses.
+pub(crate) fn declarations_of(css: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = css;
+
+    std::iter::from_fn(move || {
+        loop {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let end = declaration_end(rest);
+            let declaration = &rest[..end];
+            rest = rest.get(end + 1..).unwrap_or("");
+
+            if let Some((property, value)) = declaration.split_once(':') {
+                let (property, value) = (property.trim(), value.trim());
+                if !property.is_empty() && !value.is_empty() {
+                    return Some((property, value));
+                }
+            }
+        }
+    })
+}
+
+fn declaration_end(css: &str) -> usize {
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+    let mut escaped = false;
+
+    for (i, byte) in css.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match (quote, byte) {
+            (_, b'\\') => escaped = true,
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'(') => depth += 1,
+            (None, b')') => depth = depth.saturating_sub(1),
+            (None, b';') if depth == 0 => return i,
+            _ => {}
+        }
+    }
+
+    css.len()
+}
@@ -0,0 +1,45 @@
+//! `IntoResponse` for trees and tags, behind the `axum` feature.
+
+use crate::chunks::{CHUNK_SIZE, STREAM_THRESHOLD, TEXT_HTML};
+use crate::{Markup, MarkupBody, RegularTag, RenderOptions, SelfClosingTag};
+use ::axum_core::body::Body;
+use ::axum_core::response::{IntoResponse, Response};
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, StatusCode};
+
+/// Responds with `text/html`. Small pages are rendered up front; pages whose
+/// size hint reaches 64 KiB, or with [`Suspense`](crate::Suspense)
+/// boundaries, are streamed in chunks as the body is polled. A page that
+/// fails to render up front is answered with a 500.
+impl IntoResponse for Markup {
+    fn into_response(self) -> Response {
+        let body = if self.size_hint() < STREAM_THRESHOLD && !self.suspends() {
+            match self.render_with(&RenderOptions::default()) {
+                Ok(html) => Body::from(html),
+                Err(error) => {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response();
+                }
+            }
+        } else {
+            Body::new(MarkupBody::from(self.into_stream(CHUNK_SIZE)))
+        };
+
+        let mut response = Response::new(body);
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(TEXT_HTML));
+        response
+    }
+}
+
+impl IntoResponse for RegularTag {
+    fn into_response(self) -> Response {
+        self.into_markup().into_response()
+    }
+}
+
+impl IntoResponse for SelfClosingTag {
+    fn into_response(self) -> Response {
+        self.into_markup().into_response()
+    }
+}
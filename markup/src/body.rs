@@ -0,0 +1,60 @@
+//! A streaming [`http_body::Body`], behind the `http-body` feature.
+
+use crate::chunks::{CHUNK_SIZE, Chunks};
+use crate::{IntoMarkup, RenderError, Streaming};
+use bytes::Bytes;
+use http_body::{Body, Frame};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A response body that renders its tree as it is polled.
+///
+/// Each poll renders one chunk, so no more than a chunk of HTML is buffered
+/// and nothing is rendered while the connection is not ready for more.
+/// [`Suspense`](crate::Suspense) content is sent as it resolves. Build one
+/// from [`Streaming`] or [`Chunks`] to pick the chunk size or render options.
+#[derive(Debug)]
+pub struct MarkupBody {
+    stream: Streaming,
+    done: bool,
+}
+
+impl MarkupBody {
+    pub fn new<M: IntoMarkup>(markup: M) -> Self {
+        markup.into_markup().into_stream(CHUNK_SIZE).into()
+    }
+}
+
+impl From<Streaming> for MarkupBody {
+    fn from(stream: Streaming) -> Self {
+        MarkupBody {
+            stream,
+            done: false,
+        }
+    }
+}
+
+impl From<Chunks> for MarkupBody {
+    fn from(chunks: Chunks) -> Self {
+        Streaming::from(chunks).into()
+    }
+}
+
+impl Body for MarkupBody {
+    type Data = Bytes;
+    type Error = RenderError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, RenderError>>> {
+        let body = self.get_mut();
+        let chunk = std::task::ready!(body.stream.poll_chunk(cx));
+        body.done = chunk.is_none();
+        Poll::Ready(chunk.map(|chunk| chunk.map(|html| Frame::data(Bytes::from(html)))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{IntoMarkup, Markup};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Storage for rendered [`Cached`] subtrees.
+pub trait FragmentCache: Send + Sync {
+    fn get(&self, key: &str) -> Option<Arc<str>>;
+
+    fn insert(&self, key: &str, html: Arc<str>, ttl: Option<Duration>);
+
+    fn invalidate(&self, key: &str);
+
+    fn clear(&self);
+}
+
+/// A subtree rendered once per key and then served from a [`FragmentCache`].
+///
+/// `build` only runs on a cache miss, so neither building nor rendering is
+/// repeated while the entry is fresh.
+#[derive(Clone)]
+pub struct Cached {
+    pub key: Cow<'static, str>,
+    pub ttl: Option<Duration>,
+    build: Arc<dyn Fn() -> Markup + Send + Sync>,
+}
+
+/// An in-memory cache that evicts the least recently used entry when full.
+pub struct LruCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+struct LruState {
+    tick: u64,
+    entries: HashMap<String, Entry>,
+    /// Keys by the tick they were last used at, oldest first.
+    recency: BTreeMap<u64, String>,
+}
+
+struct Entry {
+    html: Arc<str>,
+    expires: Option<Instant>,
+    used: u64,
+}
+
+/// The cache used when [`crate::RenderOptions`] does not name one.
+pub fn global() -> &'static LruCache {
+    static GLOBAL: OnceLock<LruCache> = OnceLock::new();
+    GLOBAL.get_or_init(|| LruCache::new(256))
+}
+
+impl Cached {
+    pub fn new<K, F, C>(key: K, build: F) -> Self
+    where
+        K: Into<Cow<'static, str>>,
+        F: Fn() -> C + Send + Sync + 'static,
+        C: IntoMarkup,
+    {
+        Cached {
+            key: key.into(),
+            ttl: None,
+            build: Arc::new(move || build().into_markup()),
+        }
+    }
+
+    /// Expires the rendered entry `ttl` after it is stored.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn build(&self) -> Markup {
+        (self.build)()
+    }
+
+    pub fn into_markup(self) -> Markup {
+        Markup::Cached(self)
+    }
+}
+
+impl IntoMarkup for Cached {
+    fn into_markup(self) -> Markup {
+        Markup::Cached(self)
+    }
+}
+
+impl Markup {
+    /// Whether rendering the tree depends on the [`crate::RenderContext`],
+    /// which keeps it out of the cache.
+    pub(crate) fn reads_context(&self) -> bool {
+        match self {
+            Markup::Component(_) | Markup::Lazy(_) | Markup::Provide(_) => true,
+            Markup::RegularTag(element) => element.children.iter().any(Markup::reads_context),
+            Markup::Fragment(children) => children.iter().any(Markup::reads_context),
+            Markup::Scoped(scoped) => scoped.content.reads_context(),
+            Markup::Cached(cached) => cached.build().reads_context(),
+            Markup::Suspense(suspense) => suspense.fallback.reads_context(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Cached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cached")
+            .field("key", &self.key)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The key stands for the content, so two nodes with the same key and TTL
+/// are equal regardless of their builders.
+impl PartialEq for Cached {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.ttl == other.ttl
+    }
+}
+
+impl Eq for Cached {}
+
+impl Hash for Cached {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+        self.ttl.hash(state);
+    }
+}
+
+impl LruCache {
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(LruState {
+                tick: 0,
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl LruState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.used);
+        }
+    }
+}
+
+impl FragmentCache for LruCache {
+    fn get(&self, key: &str) -> Option<Arc<str>> {
+        let mut state = self.lock();
+        let state = &mut *state;
+        state.tick += 1;
+
+        let entry = state.entries.get_mut(key)?;
+        if entry
+            .expires
+            .is_some_and(|expires| expires <= Instant::now())
+        {
+            state.remove(key);
+            return None;
+        }
+
+        let key = state
+            .recency
+            .remove(&entry.used)
+            .expect("entries are in recency");
+        entry.used = state.tick;
+        state.recency.insert(state.tick, key);
+        Some(entry.html.clone())
+    }
+
+    fn insert(&self, key: &str, html: Arc<str>, ttl: Option<Duration>) {
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        state.remove(key);
+        if state.entries.len() >= self.capacity
+            && let Some((_, oldest)) = state.recency.pop_first()
+        {
+            state.entries.remove(&oldest);
+        }
+
+        state.entries.insert(
+            key.to_string(),
+            Entry {
+                html,
+                expires: ttl.map(|ttl| Instant::now() + ttl),
+                used: tick,
+            },
+        );
+        state.recency.insert(tick, key.to_string());
+    }
+
+    fn invalidate(&self, key: &str) {
+        self.lock().remove(key);
+    }
+
+    fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.recency.clear();
+    }
+}
+
+impl fmt::Debug for LruCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LruCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,229 @@
+//! Rendering a tree a piece at a time, for streaming response bodies.
+
+use crate::escape::escape_into;
+use crate::future::{self, Async};
+use crate::render::{self, RenderError, RenderOptions};
+use crate::suspense::{self, Suspense};
+use crate::{Markup, RenderContext};
+use std::borrow::Cow;
+
+/// Trees whose size hint reaches this many bytes are streamed by the web
+/// framework integrations rather than rendered up front.
+#[cfg(any(feature = "actix", feature = "axum"))]
+pub(crate) const STREAM_THRESHOLD: usize = 64 * 1024;
+
+/// Chunk size used when the integrations stream a body.
+#[cfg(any(feature = "actix", feature = "axum", feature = "http-body"))]
+pub(crate) const CHUNK_SIZE: usize = 16 * 1024;
+
+#[cfg(any(feature = "actix", feature = "axum"))]
+pub(crate) const TEXT_HTML: &str = "text/html; charset=utf-8";
+
+/// An iterator over the rendered HTML of a tree in pieces of roughly
+/// `chunk_size` bytes; see [`Markup::into_chunks`].
+///
+/// The tree is consumed as it is rendered, so memory held beyond the current
+/// chunk shrinks as the walk goes on. If rendering fails, the error is the
+/// last item.
+#[derive(Debug)]
+pub struct Chunks {
+    processing: Vec<Frame>,
+    options: RenderOptions,
+    chunk_size: usize,
+    /// The scoped stylesheet block, until it has been written.
+    styles: Option<String>,
+    placement: Placement,
+    /// Contexts of the enclosing [`Markup::Provide`] nodes, innermost last.
+    contexts: Vec<RenderContext>,
+    /// Whether [`Suspense`] boundaries hand their content to a [`Streaming`]
+    /// driver; otherwise only their fallbacks are rendered.
+    ///
+    /// [`Streaming`]: crate::Streaming
+    pub(crate) suspend: bool,
+    boundaries: usize,
+    suspended: Vec<(usize, Async)>,
+}
+
+#[derive(Debug)]
+enum Frame {
+    Open(Markup),
+    Close(Cow<'static, str>),
+    /// Leaves the subtree of a [`Markup::Provide`] node.
+    PopContext,
+}
+
+/// Where the stylesheet block goes, mirroring [`Markup::render`].
+#[derive(Debug, PartialEq, Eq)]
+enum Placement {
+    Placeholder,
+    Head,
+    Start,
+}
+
+impl Markup {
+    /// Renders the tree lazily, yielding the same HTML as [`Markup::render`]
+    /// in pieces of roughly `chunk_size` bytes.
+    pub fn into_chunks(self, chunk_size: usize) -> Chunks {
+        self.into_chunks_with(chunk_size, RenderOptions::default())
+    }
+
+    pub fn into_chunks_with(self, chunk_size: usize, options: RenderOptions) -> Chunks {
+        let plan = render::plan_styles(&self);
+        let styles = (!plan.styles.is_empty()).then(|| render::style_block(&plan.styles, &options));
+        let placement = if plan.placeholder {
+            Placement::Placeholder
+        } else if plan.head {
+            Placement::Head
+        } else {
+            Placement::Start
+        };
+
+        Chunks {
+            processing: vec![Frame::Open(self)],
+            options,
+            chunk_size: chunk_size.max(1),
+            styles,
+            placement,
+            contexts: Vec::new(),
+            suspend: false,
+            boundaries: 0,
+            suspended: Vec::new(),
+        }
+    }
+}
+
+impl Chunks {
+    fn step(&mut self, frame: Frame, buffer: &mut String) -> Result<(), RenderError> {
+        let node = match frame {
+            Frame::Open(node) => node,
+            Frame::Close(tag) => {
+                if self.placement == Placement::Head && tag.eq_ignore_ascii_case("head") {
+                    self.write_styles(buffer);
+                }
+                buffer.push_str("</");
+                buffer.push_str(&tag);
+                buffer.push('>');
+                return Ok(());
+            }
+            Frame::PopContext => {
+                self.contexts.pop();
+                return Ok(());
+            }
+        };
+
+        match node {
+            Markup::Text(content) => escape_into(&content, buffer),
+            Markup::Raw(content) => buffer.push_str(&content),
+            Markup::RegularTag(element) => {
+                render::open_tag(&self.options, &element.tag, &element.attributes, buffer)?;
+                buffer.push('>');
+                self.processing.push(Frame::Close(element.tag));
+                self.push_children(element.children);
+            }
+            Markup::SelfClosingTag(element) => {
+                render::open_tag(&self.options, &element.tag, &element.attributes, buffer)?;
+                buffer.push_str(" />");
+            }
+            Markup::Fragment(children) => self.push_children(children),
+            Markup::Scoped(scoped) => self.processing.push(Frame::Open(*scoped.content)),
+            Markup::ScopedStyles => {
+                if self.placement == Placement::Placeholder {
+                    self.write_styles(buffer);
+                }
+            }
+            Markup::Cached(cached) => {
+                let context = self.contexts.last().unwrap_or(&self.options.context);
+                render::render_cached(&self.options, context, &cached, buffer)?;
+            }
+            Markup::Async(_) => return Err(RenderError::Unresolved),
+            Markup::Suspense(suspense) => self.suspend(suspense, buffer),
+            Markup::Component(component) => {
+                let context = self.contexts.last().unwrap_or(&self.options.context);
+                render::render_component(&self.options, context, &component, buffer)?;
+            }
+            Markup::Lazy(lazy) => {
+                let context = self.contexts.last().unwrap_or(&self.options.context);
+                lazy.write_into(buffer, context)?;
+            }
+            Markup::Provide(provide) => {
+                let context = self.contexts.last().unwrap_or(&self.options.context);
+                self.contexts.push(context.merged(&provide.context));
+                self.processing.push(Frame::PopContext);
+                self.processing.push(Frame::Open(*provide.content));
+            }
+            Markup::None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Writes the start marker of a boundary and queues its end marker after
+    /// the fallback.
+    fn suspend(&mut self, suspense: Suspense, buffer: &mut String) {
+        if self.suspend {
+            let content = suspense.content.future();
+            let id = self.boundaries;
+            self.boundaries += 1;
+            buffer.push_str(&format!(
+                r#"<template id="{}"></template>"#,
+                suspense::id(id)
+            ));
+            self.processing.push(Frame::Open(Markup::Raw(
+                format!("<!--/{}-->", suspense::id(id)).into(),
+            )));
+            let context = self.contexts.last().cloned();
+            let content = Async::new(async move {
+                let content = future::resolve(content.await).await;
+                match context {
+                    Some(context) => Markup::provide_context(context, content),
+                    None => content,
+                }
+            });
+            self.suspended.push((id, content));
+        }
+        self.processing.push(Frame::Open(*suspense.fallback));
+    }
+
+    /// Hands over the contents of boundaries rendered since the last call.
+    pub(crate) fn take_suspended(&mut self) -> Vec<(usize, Async)> {
+        std::mem::take(&mut self.suspended)
+    }
+
+    pub(crate) fn options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    fn push_children(&mut self, children: Vec<Markup>) {
+        self.processing
+            .extend(children.into_iter().rev().map(Frame::Open));
+    }
+
+    fn write_styles(&mut self, buffer: &mut String) {
+        if let Some(styles) = self.styles.take() {
+            buffer.push_str(&styles);
+        }
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Result<String, RenderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = String::with_capacity(self.chunk_size);
+        if self.placement == Placement::Start {
+            self.write_styles(&mut buffer);
+        }
+
+        while buffer.len() < self.chunk_size {
+            let Some(frame) = self.processing.pop() else {
+                break;
+            };
+            if let Err(error) = self.step(frame, &mut buffer) {
+                self.processing.clear();
+                return Some(Err(error));
+            }
+        }
+
+        (!buffer.is_empty()).then_some(Ok(buffer))
+    }
+}
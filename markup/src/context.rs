@@ -0,0 +1,185 @@
+use crate::{IntoMarkup, Markup};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Values available to [`Component`] nodes while rendering, one per type.
+///
+/// Set the request-wide values with
+/// [`RenderOptions::context`](crate::RenderOptions::context) and override
+/// them for a subtree with [`Markup::provide`]. Cloning is cheap.
+#[derive(Clone, Default)]
+pub struct RenderContext {
+    values: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+/// A node built at render time from the current [`RenderContext`].
+///
+/// The built subtree is rendered on its own, so the stylesheets of scoped
+/// subtrees inside it are emitted at its start, as with [`Cached`](crate::Cached).
+#[derive(Clone)]
+pub struct Component {
+    build: Arc<dyn Fn(&RenderContext) -> Markup + Send + Sync>,
+}
+
+/// A subtree rendered with extra context values, see [`Markup::provide`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provide {
+    pub context: RenderContext,
+    pub content: Box<Markup>,
+}
+
+impl RenderContext {
+    pub fn new() -> Self {
+        RenderContext::default()
+    }
+
+    /// Adds `value`, replacing any earlier value of the same type.
+    pub fn with<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        Arc::make_mut(&mut self.values).insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// This context with the values of `overrides` on top.
+    pub(crate) fn merged(&self, overrides: &RenderContext) -> RenderContext {
+        if overrides.is_empty() {
+            return self.clone();
+        }
+        if self.is_empty() {
+            return overrides.clone();
+        }
+
+        let mut values = (*self.values).clone();
+        values.extend(
+            overrides
+                .values
+                .iter()
+                .map(|(id, value)| (*id, value.clone())),
+        );
+        RenderContext {
+            values: Arc::new(values),
+        }
+    }
+}
+
+impl Component {
+    pub fn new<F, C>(build: F) -> Self
+    where
+        F: Fn(&RenderContext) -> C + Send + Sync + 'static,
+        C: IntoMarkup,
+    {
+        Component {
+            build: Arc::new(move |context| build(context).into_markup()),
+        }
+    }
+
+    pub fn build(&self, context: &RenderContext) -> Markup {
+        (self.build)(context)
+    }
+
+    pub fn into_markup(self) -> Markup {
+        Markup::Component(self)
+    }
+}
+
+impl Markup {
+    /// A [`Component`] built from the context in effect where it is rendered.
+    pub fn component<F, C>(build: F) -> Markup
+    where
+        F: Fn(&RenderContext) -> C + Send + Sync + 'static,
+        C: IntoMarkup,
+    {
+        Markup::Component(Component::new(build))
+    }
+
+    /// Renders `content` with `value` replacing the context value of its
+    /// type, e.g. a different locale inside one section.
+    pub fn provide<T, C>(value: T, content: C) -> Markup
+    where
+        T: Send + Sync + 'static,
+        C: IntoMarkup,
+    {
+        Markup::provide_context(RenderContext::new().with(value), content)
+    }
+
+    /// Like [`Markup::provide`] with several values at once.
+    pub fn provide_context<C: IntoMarkup>(context: RenderContext, content: C) -> Markup {
+        Markup::Provide(Provide {
+            context,
+            content: Box::new(content.into_markup()),
+        })
+    }
+}
+
+impl IntoMarkup for Component {
+    fn into_markup(self) -> Markup {
+        Markup::Component(self)
+    }
+}
+
+impl fmt::Debug for RenderContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderContext")
+            .field("len", &self.values.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Component").finish_non_exhaustive()
+    }
+}
+
+/// Contexts hold arbitrary values, so they are equal only when they share
+/// their storage.
+impl PartialEq for RenderContext {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.values, &other.values)
+    }
+}
+
+impl Eq for RenderContext {}
+
+impl Hash for RenderContext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.values).hash(state);
+    }
+}
+
+/// Nodes are equal only when they share a builder.
+impl PartialEq for Component {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.build, &other.build)
+    }
+}
+
+impl Eq for Component {}
+
+impl Hash for Component {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.build).cast::<()>().hash(state);
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// A nonce with characters outside base64, which could break out of the
+/// header it is written into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNonce(pub String);
+
+/// Builds a `Content-Security-Policy` header value allowing only scripts and
+/// styles that carry `nonce`, as added by [`crate::RenderOptions::nonce`].
+pub fn header_value(nonce: &str) -> Result<String, InvalidNonce> {
+    if !is_valid_nonce(nonce) {
+        return Err(InvalidNonce(nonce.to_string()));
+    }
+    Ok(format!(
+        "default-src 'self'; script-src 'nonce-{nonce}' 'strict-dynamic'; style-src 'self' 'nonce-{nonce}'; object-src 'none'; base-uri 'none'"
+    ))
+}
+
+/// Returns whether `nonce` only uses base64 characters, as CSP requires.
+pub fn is_valid_nonce(nonce: &str) -> bool {
+    !nonce.is_empty()
+        && nonce
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_'))
+}
+
+impl fmt::Display for InvalidNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CSP nonce {:?} is not base64", self.0)
+    }
+}
+
+impl std::error::Error for InvalidNonce {}
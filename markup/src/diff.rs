@@ -0,0 +1,409 @@
+//! Structural diffs between two [`Markup`] trees.
+//!
+//! Trees are compared in their [`Markup::normalized`] form, so a path is the
+//! list of child indices from the root as the browser sees them: fragments
+//! are flattened and [`Scoped`](crate::Scoped) wrappers are transparent.
+//! Patches apply in order, each path referring to the tree as the patches
+//! before it left it.
+
+use crate::{AttrValue, Attribute, Markup};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "op", rename_all = "snake_case"))]
+pub enum Patch {
+    /// Inserts `node` so that it ends up at `path`.
+    Insert {
+        path: Vec<usize>,
+        node: Markup,
+    },
+    Remove {
+        path: Vec<usize>,
+    },
+    Replace {
+        path: Vec<usize>,
+        node: Markup,
+    },
+    /// Moves a child within its parent; `to` is its index once it has been
+    /// taken out.
+    Move {
+        path: Vec<usize>,
+        to: usize,
+    },
+    SetAttribute {
+        path: Vec<usize>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "crate::serialize::attribute_name")
+        )]
+        name: Cow<'static, str>,
+        value: AttrValue,
+    },
+    RemoveAttribute {
+        path: Vec<usize>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "crate::serialize::attribute_name")
+        )]
+        name: Cow<'static, str>,
+    },
+    UpdateText {
+        path: Vec<usize>,
+        text: Cow<'static, str>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The path names no node, or a node the patch cannot apply to.
+    InvalidPath(Vec<usize>),
+}
+
+impl Markup {
+    /// Lists the patches that turn `old` into `new`.
+    ///
+    /// Children carrying a `key` attribute are matched by key rather than by
+    /// position, so reordering a keyed list produces moves instead of
+    /// rewriting every item.
+    pub fn diff(old: &Markup, new: &Markup) -> Vec<Patch> {
+        let mut patches = Vec::new();
+        diff_node(
+            &old.normalized(),
+            &new.normalized(),
+            &mut Vec::new(),
+            &mut patches,
+        );
+        patches
+    }
+
+    /// Normalizes the tree and applies `patches` from [`Markup::diff`] to it.
+    /// Patches before a failing one stay applied.
+    pub fn apply(&mut self, patches: &[Patch]) -> Result<(), PatchError> {
+        *self = self.normalized();
+        patches
+            .iter()
+            .try_for_each(|patch| apply_patch(self, patch))
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidPath(path) => write!(f, "no node at {path:?} to patch"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+fn diff_node(old: &Markup, new: &Markup, path: &mut Vec<usize>, patches: &mut Vec<Patch>) {
+    if old == new {
+        return;
+    }
+    if !patchable(old, new) {
+        patches.push(Patch::Replace {
+            path: path.clone(),
+            node: new.clone(),
+        });
+        return;
+    }
+
+    match (old, new) {
+        (Markup::Text(_), Markup::Text(text)) => patches.push(Patch::UpdateText {
+            path: path.clone(),
+            text: text.clone(),
+        }),
+        (Markup::RegularTag(old), Markup::RegularTag(new)) => {
+            diff_attributes(&old.attributes, &new.attributes, path, patches);
+            diff_children(&old.children, &new.children, path, patches);
+        }
+        (Markup::SelfClosingTag(old), Markup::SelfClosingTag(new)) => {
+            diff_attributes(&old.attributes, &new.attributes, path, patches);
+        }
+        (Markup::Fragment(old), Markup::Fragment(new)) => {
+            diff_children(old, new, path, patches);
+        }
+        (Markup::Scoped(old), Markup::Scoped(new)) => {
+            diff_node(&old.content, &new.content, path, patches);
+        }
+        _ => unreachable!("patchable nodes are handled above"),
+    }
+}
+
+/// Whether `old` can be patched into `new` in place rather than replaced.
+fn patchable(old: &Markup, new: &Markup) -> bool {
+    match (old, new) {
+        (Markup::Text(_), Markup::Text(_)) | (Markup::Fragment(_), Markup::Fragment(_)) => true,
+        (Markup::RegularTag(old), Markup::RegularTag(new)) => old.tag == new.tag,
+        (Markup::SelfClosingTag(old), Markup::SelfClosingTag(new)) => old.tag == new.tag,
+        (Markup::Scoped(old), Markup::Scoped(new)) => {
+            old.css == new.css && patchable(&old.content, &new.content)
+        }
+        _ => false,
+    }
+}
+
+fn diff_attributes(old: &[Attribute], new: &[Attribute], path: &[usize], patches: &mut Vec<Patch>) {
+    for attr in old {
+        if !new.iter().any(|new| new.name == attr.name) {
+            patches.push(Patch::RemoveAttribute {
+                path: path.to_vec(),
+                name: attr.name.clone(),
+            });
+        }
+    }
+
+    for attr in new {
+        if !old.contains(attr) {
+            patches.push(Patch::SetAttribute {
+                path: path.to_vec(),
+                name: attr.name.clone(),
+                value: attr.value.clone(),
+            });
+        }
+    }
+}
+
+fn diff_children(old: &[Markup], new: &[Markup], path: &mut Vec<usize>, patches: &mut Vec<Patch>) {
+    if old.iter().chain(new).any(|node| key_of(node).is_some()) {
+        diff_keyed(old, new, path, patches);
+        return;
+    }
+
+    let common = old.len().min(new.len());
+    for i in 0..common {
+        path.push(i);
+        diff_node(&old[i], &new[i], path, patches);
+        path.pop();
+    }
+    for (i, node) in new.iter().enumerate().skip(common) {
+        patches.push(Patch::Insert {
+            path: child(path, i),
+            node: node.clone(),
+        });
+    }
+    for i in (new.len()..old.len()).rev() {
+        patches.push(Patch::Remove {
+            path: child(path, i),
+        });
+    }
+}
+
+/// Reconciles a list where some children are keyed: each new child continues
+/// the old child with its key, or the next unused unkeyed one. Children whose
+/// order already agrees stay put and the rest are moved around them.
+fn diff_keyed(old: &[Markup], new: &[Markup], path: &mut Vec<usize>, patches: &mut Vec<Patch>) {
+    let mut keyed: HashMap<&str, usize> = HashMap::new();
+    for (i, node) in old.iter().enumerate().rev() {
+        if let Some(key) = key_of(node) {
+            keyed.insert(key, i);
+        }
+    }
+    let mut unkeyed = (0..old.len()).filter(|&i| key_of(&old[i]).is_none());
+    let sources: Vec<Option<usize>> = new
+        .iter()
+        .map(|node| match key_of(node) {
+            Some(key) => keyed.remove(key),
+            None => unkeyed.next(),
+        })
+        .collect();
+
+    let mut targets = vec![None; old.len()];
+    for (i, source) in sources.iter().enumerate() {
+        if let Some(source) = *source {
+            targets[source] = Some(i);
+        }
+    }
+
+    for i in (0..old.len()).rev() {
+        if targets[i].is_none() {
+            patches.push(Patch::Remove {
+                path: child(path, i),
+            });
+        }
+    }
+
+    // The surviving children in their current order, by their index in `new`.
+    let mut current: Vec<usize> = targets.into_iter().flatten().collect();
+    let stable = longest_increasing(&current, new.len());
+
+    // Walking backwards, every child that is not stable is placed directly
+    // before its already placed successor.
+    for i in (0..new.len()).rev() {
+        let anchor = if i + 1 == new.len() {
+            current.len()
+        } else {
+            position(&current, i + 1)
+        };
+        if sources[i].is_none() {
+            current.insert(anchor, i);
+            patches.push(Patch::Insert {
+                path: child(path, anchor),
+                node: new[i].clone(),
+            });
+        } else if !stable[i] {
+            let from = position(&current, i);
+            let to = if from < anchor { anchor - 1 } else { anchor };
+            if from != to {
+                current.remove(from);
+                current.insert(to, i);
+                patches.push(Patch::Move {
+                    path: child(path, from),
+                    to,
+                });
+            }
+        }
+    }
+
+    for (i, source) in sources.into_iter().enumerate() {
+        if let Some(source) = source {
+            path.push(i);
+            diff_node(&old[source], &new[i], path, patches);
+            path.pop();
+        }
+    }
+}
+
+fn key_of(node: &Markup) -> Option<&str> {
+    let attributes = match node {
+        Markup::RegularTag(element) => &element.attributes,
+        Markup::SelfClosingTag(element) => &element.attributes,
+        Markup::Scoped(scoped) => return key_of(&scoped.content),
+        _ => return None,
+    };
+    attributes
+        .iter()
+        .find(|attr| attr.name == "key")
+        .and_then(|attr| attr.value.as_str())
+}
+
+/// Marks the values of one longest strictly increasing subsequence of
+/// `values`, each of which is below `len`.
+fn longest_increasing(values: &[usize], len: usize) -> Vec<bool> {
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; values.len()];
+
+    for (i, &value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&tail| values[tail] < value);
+        if length > 0 {
+            previous[i] = Some(tails[length - 1]);
+        }
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+
+    let mut marked = vec![false; len];
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        marked[values[i]] = true;
+        next = previous[i];
+    }
+    marked
+}
+
+fn position(current: &[usize], index: usize) -> usize {
+    current
+        .iter()
+        .position(|&i| i == index)
+        .expect("placed children are tracked")
+}
+
+fn child(path: &[usize], index: usize) -> Vec<usize> {
+    let mut path = path.to_vec();
+    path.push(index);
+    path
+}
+
+fn apply_patch(root: &mut Markup, patch: &Patch) -> Result<(), PatchError> {
+    match patch {
+        Patch::Insert { path, node } => {
+            let (children, index) = parent_of(root, path)?;
+            if index > children.len() {
+                return Err(invalid(path));
+            }
+            children.insert(index, node.clone());
+        }
+        Patch::Remove { path } => {
+            let (children, index) = parent_of(root, path)?;
+            if index >= children.len() {
+                return Err(invalid(path));
+            }
+            children.remove(index);
+        }
+        Patch::Move { path, to } => {
+            let (children, from) = parent_of(root, path)?;
+            if from >= children.len() || *to >= children.len() {
+                return Err(invalid(path));
+            }
+            let node = children.remove(from);
+            children.insert(*to, node);
+        }
+        Patch::Replace { path, node } => *node_at(root, path)? = node.clone(),
+        Patch::SetAttribute { path, name, value } => {
+            let attributes = attributes_of(node_at(root, path)?).ok_or_else(|| invalid(path))?;
+            match attributes.iter_mut().find(|attr| attr.name == *name) {
+                Some(attr) => attr.value = value.clone(),
+                None => attributes.push(Attribute {
+                    name: name.clone(),
+                    value: value.clone(),
+                }),
+            }
+        }
+        Patch::RemoveAttribute { path, name } => {
+            let attributes = attributes_of(node_at(root, path)?).ok_or_else(|| invalid(path))?;
+            attributes.retain(|attr| attr.name != *name);
+        }
+        Patch::UpdateText { path, text } => match node_at(root, path)? {
+            Markup::Text(content) => *content = text.clone(),
+            _ => return Err(invalid(path)),
+        },
+    }
+    Ok(())
+}
+
+fn node_at<'a>(mut node: &'a mut Markup, path: &[usize]) -> Result<&'a mut Markup, PatchError> {
+    for &index in path {
+        node = children_of(node)
+            .and_then(|children| children.get_mut(index))
+            .ok_or_else(|| invalid(path))?;
+    }
+    Ok(node)
+}
+
+fn parent_of<'a>(
+    root: &'a mut Markup,
+    path: &[usize],
+) -> Result<(&'a mut Vec<Markup>, usize), PatchError> {
+    let (&index, parent) = path.split_last().ok_or_else(|| invalid(path))?;
+    let children = children_of(node_at(root, parent)?).ok_or_else(|| invalid(path))?;
+    Ok((children, index))
+}
+
+/// The child list of `node`, looking through scoped wrappers.
+fn children_of(node: &mut Markup) -> Option<&mut Vec<Markup>> {
+    match node {
+        Markup::RegularTag(element) => Some(&mut element.children),
+        Markup::Fragment(children) => Some(children),
+        Markup::Scoped(scoped) => children_of(&mut scoped.content),
+        _ => None,
+    }
+}
+
+fn attributes_of(node: &mut Markup) -> Option<&mut Vec<Attribute>> {
+    match node {
+        Markup::RegularTag(element) => Some(&mut element.attributes),
+        Markup::SelfClosingTag(element) => Some(&mut element.attributes),
+        Markup::Scoped(scoped) => attributes_of(&mut scoped.content),
+        _ => None,
+    }
+}
+
+fn invalid(path: &[usize]) -> PatchError {
+    PatchError::InvalidPath(path.to_vec())
+}
@@ -0,0 +1,89 @@
+/// Escapes HTML special characters in `s`, appending the result to `buffer`.
+///
+/// Runs without special characters are copied in bulk. Eight bytes are checked
+/// at a time with SWAR arithmetic, so clean text costs roughly one comparison
+/// per word instead of one per character.
+pub(crate) fn escape_into(s: &str, buffer: &mut String) {
+    let bytes = s.as_bytes();
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(chunk) = bytes.get(i..i + 8) {
+            let word = u64::from_le_bytes(chunk.try_into().unwrap());
+            if !has_special(word) {
+                i += 8;
+                continue;
+            }
+        }
+
+        let end = (i + 8).min(bytes.len());
+        while i < end {
+            if let Some(entity) = entity(bytes[i]) {
+                buffer.push_str(&s[copied..i]);
+                buffer.push_str(entity);
+                copied = i + 1;
+            }
+            i += 1;
+        }
+    }
+
+    buffer.push_str(&s[copied..]);
+}
+
+/// Length of `s` once escaped, counted with the same word-at-a-time scan.
+pub(crate) fn escaped_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut len = bytes.len();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(chunk) = bytes.get(i..i + 8) {
+            let word = u64::from_le_bytes(chunk.try_into().unwrap());
+            if !has_special(word) {
+                i += 8;
+                continue;
+            }
+        }
+
+        let end = (i + 8).min(bytes.len());
+        for byte in &bytes[i..end] {
+            if let Some(entity) = entity(*byte) {
+                len += entity.len() - 1;
+            }
+        }
+        i = end;
+    }
+
+    len
+}
+
+const fn entity(byte: u8) -> Option<&'static str> {
+    match byte {
+        b'&' => Some("&amp;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        b'"' => Some("&quot;"),
+        b'\'' => Some("&apos;"),
+        _ => None,
+    }
+}
+
+const LO: u64 = 0x0101_0101_0101_0101;
+const HI: u64 = 0x8080_8080_8080_8080;
+
+/// Returns whether any byte of `word` is one of `& < > " '`.
+#[inline]
+fn has_special(word: u64) -> bool {
+    has_byte(word, b'&')
+        | has_byte(word, b'<')
+        | has_byte(word, b'>')
+        | has_byte(word, b'"')
+        | has_byte(word, b'\'')
+}
+
+#[inline]
+fn has_byte(word: u64, byte: u8) -> bool {
+    let x = word ^ (LO * byte as u64);
+    x.wrapping_sub(LO) & !x & HI != 0
+}
@@ -0,0 +1,197 @@
+use crate::{AttrValue, Attribute, Markup, Provide, RenderError, RenderOptions, Scoped};
+use std::ptr;
+
+/// An element picked out of a tree, with the scoped and provide nodes
+/// around it, outermost first.
+struct Found<'a> {
+    node: &'a Markup,
+    wrappers: Vec<&'a Markup>,
+}
+
+/// What [`collect`] does with a node.
+enum Visit {
+    Take,
+    Skip,
+    Descend,
+}
+
+impl Markup {
+    /// Renders only the element whose `id` attribute is `id`, so a partial
+    /// request, such as an htmx one, can be answered from the full-page
+    /// template. Returns `None` when no element has that id.
+    ///
+    /// Stylesheets of scoped subtrees and context values provided around the
+    /// element are kept. The contents of [`Cached`](crate::Cached) and
+    /// [`Component`](crate::Component) nodes are not searched. Panics like
+    /// [`Markup::render`].
+    pub fn render_fragment(&self, id: &str) -> Option<String> {
+        self.render_fragment_with(id, &RenderOptions::default())
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn render_fragment_with(
+        &self,
+        id: &str,
+        options: &RenderOptions,
+    ) -> Result<Option<String>, RenderError> {
+        find(self, id)
+            .map(|found| render(&found, options))
+            .transpose()
+    }
+
+    /// Like [`Markup::render_fragment`], followed by every element elsewhere
+    /// in the tree marked `hx-swap-oob`, so one response updates several
+    /// parts of the page.
+    pub fn render_oob(&self, id: &str) -> Option<String> {
+        self.render_oob_with(id, &RenderOptions::default())
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn render_oob_with(
+        &self,
+        id: &str,
+        options: &RenderOptions,
+    ) -> Result<Option<String>, RenderError> {
+        let Some(main) = find(self, id) else {
+            return Ok(None);
+        };
+
+        // Elements around the main one are already on the page and those
+        // inside it are sent with it, so neither is sent out of band.
+        let mut oob = Vec::new();
+        let visit = |node: &Markup| {
+            if ptr::eq(node, main.node) {
+                Visit::Skip
+            } else if is_oob(node) && !contains(node, main.node) {
+                Visit::Take
+            } else {
+                Visit::Descend
+            }
+        };
+        collect(self, &mut Vec::new(), &visit, &mut oob, false);
+
+        let mut html = render(&main, options)?;
+        for found in &oob {
+            html.push_str(&render(found, options)?);
+        }
+        Ok(Some(html))
+    }
+}
+
+/// The first element in document order whose `id` attribute is `id`.
+fn find<'a>(root: &'a Markup, id: &str) -> Option<Found<'a>> {
+    let mut found = Vec::new();
+    let visit = |node: &Markup| {
+        if has_id(node, id) {
+            Visit::Take
+        } else {
+            Visit::Descend
+        }
+    };
+    collect(root, &mut Vec::new(), &visit, &mut found, true);
+    found.pop()
+}
+
+fn contains(root: &Markup, target: &Markup) -> bool {
+    let mut found = Vec::new();
+    let visit = |node: &Markup| {
+        if ptr::eq(node, target) {
+            Visit::Take
+        } else {
+            Visit::Descend
+        }
+    };
+    collect(root, &mut Vec::new(), &visit, &mut found, true);
+    !found.is_empty()
+}
+
+/// Walks the tree in document order, collecting the nodes `visit` takes
+/// without looking inside them.
+fn collect<'a>(
+    node: &'a Markup,
+    wrappers: &mut Vec<&'a Markup>,
+    visit: &impl Fn(&Markup) -> Visit,
+    found: &mut Vec<Found<'a>>,
+    first_only: bool,
+) {
+    if first_only && !found.is_empty() {
+        return;
+    }
+    match visit(node) {
+        Visit::Take => {
+            found.push(Found {
+                node,
+                wrappers: wrappers.clone(),
+            });
+            return;
+        }
+        Visit::Skip => return,
+        Visit::Descend => {}
+    }
+
+    match node {
+        Markup::RegularTag(element) => {
+            for child in &element.children {
+                collect(child, wrappers, visit, found, first_only);
+            }
+        }
+        Markup::Fragment(children) => {
+            for child in children {
+                collect(child, wrappers, visit, found, first_only);
+            }
+        }
+        Markup::Scoped(Scoped { content, .. }) | Markup::Provide(Provide { content, .. }) => {
+            wrappers.push(node);
+            collect(content, wrappers, visit, found, first_only);
+            wrappers.pop();
+        }
+        _ => {}
+    }
+}
+
+fn render(found: &Found, options: &RenderOptions) -> Result<String, RenderError> {
+    if found.wrappers.is_empty() {
+        return found.node.render_with(options);
+    }
+
+    let mut node = found.node.clone();
+    for wrapper in found.wrappers.iter().rev() {
+        let content = Box::new(node);
+        node = match wrapper {
+            Markup::Scoped(scoped) => Markup::Scoped(Scoped {
+                css: scoped.css.clone(),
+                content,
+            }),
+            Markup::Provide(provide) => Markup::Provide(Provide {
+                context: provide.context.clone(),
+                content,
+            }),
+            _ => unreachable!(),
+        };
+    }
+    node.render_with(options)
+}
+
+fn has_id(node: &Markup, id: &str) -> bool {
+    let Markup::RegularTag(element) = node else {
+        return false;
+    };
+    attribute(&element.attributes, "id").is_some_and(|value| value.as_str() == Some(id))
+}
+
+fn is_oob(node: &Markup) -> bool {
+    let attributes = match node {
+        Markup::RegularTag(element) => &element.attributes,
+        Markup::SelfClosingTag(element) => &element.attributes,
+        _ => return false,
+    };
+    attribute(attributes, "hx-swap-oob")
+        .is_some_and(|value| !matches!(value, AttrValue::Bool(false)))
+}
+
+fn attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a AttrValue> {
+    attributes
+        .iter()
+        .find(|attr| attr.name.eq_ignore_ascii_case(name))
+        .map(|attr| &attr.value)
+}
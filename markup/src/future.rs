@@ -0,0 +1,189 @@
+use crate::{IntoMarkup, Markup, RenderError, RenderOptions};
+use futures_util::FutureExt;
+use std::fmt;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+pub(crate) type BoxFuture = Pin<Box<dyn Future<Output = Markup> + Send>>;
+
+/// A component whose markup is produced by a future, such as one that loads
+/// its own data.
+///
+/// It must be resolved with [`Markup::resolve`] or [`Markup::render_async`]
+/// before rendering; the synchronous renderers fail with
+/// [`RenderError::Unresolved`] on an unresolved node.
+/// Clones share the future, which runs once; every tree it is resolved in
+/// receives a copy of its markup.
+#[derive(Clone)]
+pub struct Async {
+    future: Arc<dyn Fn() -> BoxFuture + Send + Sync>,
+}
+
+impl Async {
+    pub fn new<F, C>(future: F) -> Self
+    where
+        F: Future<Output = C> + Send + 'static,
+        C: IntoMarkup,
+    {
+        let future: BoxFuture = Box::pin(async move { future.await.into_markup() });
+        let shared = future.shared();
+        Async {
+            future: Arc::new(move || Box::pin(shared.clone())),
+        }
+    }
+
+    pub fn into_markup(self) -> Markup {
+        Markup::Async(self)
+    }
+
+    pub(crate) fn future(&self) -> BoxFuture {
+        (self.future)()
+    }
+}
+
+impl Markup {
+    /// Wraps a future as an [`Async`] node.
+    pub fn from_future<F, C>(future: F) -> Markup
+    where
+        F: Future<Output = C> + Send + 'static,
+        C: IntoMarkup,
+    {
+        Markup::Async(Async::new(future))
+    }
+
+    /// Replaces every [`Async`] node with the markup its future produces,
+    /// including nodes that appear in that markup. [`Suspense`](crate::Suspense)
+    /// boundaries are replaced by their content.
+    ///
+    /// All futures are polled concurrently on the calling task, so it works
+    /// under any executor; the tree keeps its order whatever order they
+    /// finish in.
+    pub fn resolve(self) -> impl Future<Output = Markup> + Send {
+        resolve(self)
+    }
+
+    pub async fn render_async(self) -> String {
+        self.resolve().await.render()
+    }
+
+    pub async fn render_async_with(self, options: &RenderOptions) -> Result<String, RenderError> {
+        self.resolve().await.render_with(options)
+    }
+}
+
+pub(crate) fn resolve(mut root: Markup) -> BoxFuture {
+    let mut futures = Vec::new();
+    visit(&mut root, &mut |node| match node {
+        Markup::Async(component) => futures.push(component.future()),
+        Markup::Suspense(suspense) => futures.push(suspense.content.future()),
+        _ => {}
+    });
+    if futures.is_empty() {
+        return Box::pin(std::future::ready(root));
+    }
+
+    let slots = futures
+        .into_iter()
+        .map(|future| Slot::Pending(Box::pin(async move { resolve(future.await).await })))
+        .collect();
+
+    Box::pin(async move {
+        let mut resolved = JoinAll { slots }.await.into_iter();
+        visit(&mut root, &mut |node| {
+            *node = resolved.next().unwrap_or(Markup::None);
+        });
+        root
+    })
+}
+
+/// Calls `f` on every [`Async`] and [`Suspense`](crate::Suspense) node in
+/// document order.
+fn visit(node: &mut Markup, f: &mut impl FnMut(&mut Markup)) {
+    match node {
+        Markup::RegularTag(element) => {
+            for child in &mut element.children {
+                visit(child, f);
+            }
+        }
+        Markup::Fragment(children) => {
+            for child in children {
+                visit(child, f);
+            }
+        }
+        Markup::Scoped(scoped) => visit(&mut scoped.content, f),
+        Markup::Provide(provide) => visit(&mut provide.content, f),
+        Markup::Async(_) | Markup::Suspense(_) => f(node),
+        _ => {}
+    }
+}
+
+enum Slot {
+    Pending(BoxFuture),
+    Done(Markup),
+}
+
+/// Polls every future on each wake until all are done.
+struct JoinAll {
+    slots: Vec<Slot>,
+}
+
+impl Future for JoinAll {
+    type Output = Vec<Markup>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut done = true;
+        for slot in &mut self.slots {
+            if let Slot::Pending(future) = slot {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(markup) => *slot = Slot::Done(markup),
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+        if !done {
+            return Poll::Pending;
+        }
+
+        let slots = mem::take(&mut self.slots);
+        Poll::Ready(
+            slots
+                .into_iter()
+                .map(|slot| match slot {
+                    Slot::Done(markup) => markup,
+                    Slot::Pending(_) => unreachable!(),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl IntoMarkup for Async {
+    fn into_markup(self) -> Markup {
+        Markup::Async(self)
+    }
+}
+
+impl fmt::Debug for Async {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Async").finish_non_exhaustive()
+    }
+}
+
+/// Nodes are equal only when they share a future.
+impl PartialEq for Async {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.future, &other.future)
+    }
+}
+
+impl Eq for Async {}
+
+impl Hash for Async {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.future).cast::<()>().hash(state);
+    }
+}
@@ -0,0 +1,430 @@
+//! Typed helpers for [htmx](https://htmx.org) attributes.
+//!
+//! Every value is built from enums and small builders rather than hand-typed
+//! strings, so a misspelled swap strategy or trigger modifier fails to
+//! compile instead of being ignored by the browser. Values go through the
+//! normal attribute escaping when rendered.
+
+use crate::{AttrValue, RegularTag, SelfClosingTag};
+use std::fmt;
+use std::time::Duration;
+
+/// How the response is placed relative to the target, for `hx-swap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swap {
+    InnerHtml,
+    OuterHtml,
+    TextContent,
+    BeforeBegin,
+    AfterBegin,
+    BeforeEnd,
+    AfterEnd,
+    Delete,
+    None,
+}
+
+/// A CSS selector. [`Selector::id`] and [`Selector::class`] escape their
+/// argument as a CSS identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector(String);
+
+/// An htmx extended selector, for `hx-target`, `hx-indicator` and a
+/// trigger's `from:` modifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    This,
+    Document,
+    Window,
+    Selector(Selector),
+    Closest(Selector),
+    Find(Selector),
+    Next(Option<Selector>),
+    Previous(Option<Selector>),
+}
+
+/// One entry of `hx-trigger`: an event with its modifiers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    event: TriggerEvent,
+    filter: Option<&'static str>,
+    once: bool,
+    changed: bool,
+    delay: Option<Duration>,
+    throttle: Option<Duration>,
+    from: Option<Target>,
+    consume: bool,
+    queue: Option<Queue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TriggerEvent {
+    Named(&'static str),
+    Every(Duration),
+}
+
+/// Which requests are kept while one is already in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Queue {
+    First,
+    Last,
+    All,
+    None,
+}
+
+impl Swap {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Swap::InnerHtml => "innerHTML",
+            Swap::OuterHtml => "outerHTML",
+            Swap::TextContent => "textContent",
+            Swap::BeforeBegin => "beforebegin",
+            Swap::AfterBegin => "afterbegin",
+            Swap::BeforeEnd => "beforeend",
+            Swap::AfterEnd => "afterend",
+            Swap::Delete => "delete",
+            Swap::None => "none",
+        }
+    }
+}
+
+impl Selector {
+    pub fn id(id: &str) -> Self {
+        Selector(format!("#{}", css_ident(id)))
+    }
+
+    pub fn class(class: &str) -> Self {
+        Selector(format!(".{}", css_ident(class)))
+    }
+
+    /// Wraps an arbitrary selector, such as `form input[name=q]`, as is.
+    pub fn css<S: Into<String>>(selector: S) -> Self {
+        Selector(selector.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Trigger {
+    /// A trigger on a DOM event such as `click` or `keyup`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or contains whitespace, a comma or a
+    /// bracket, which would run into the next part of the attribute.
+    pub fn event(name: &'static str) -> Self {
+        assert!(
+            !name.is_empty()
+                && !name
+                    .chars()
+                    .any(|c| c.is_whitespace() || matches!(c, ',' | '[' | ']')),
+            "invalid htmx trigger event name `{name}`"
+        );
+        Trigger::new(TriggerEvent::Named(name))
+    }
+
+    pub fn click() -> Self {
+        Trigger::event("click")
+    }
+
+    pub fn change() -> Self {
+        Trigger::event("change")
+    }
+
+    pub fn submit() -> Self {
+        Trigger::event("submit")
+    }
+
+    pub fn keyup() -> Self {
+        Trigger::event("keyup")
+    }
+
+    pub fn load() -> Self {
+        Trigger::event("load")
+    }
+
+    pub fn revealed() -> Self {
+        Trigger::event("revealed")
+    }
+
+    pub fn intersect() -> Self {
+        Trigger::event("intersect")
+    }
+
+    /// Polls on an interval instead of waiting for an event.
+    pub fn every(interval: Duration) -> Self {
+        Trigger::new(TriggerEvent::Every(interval))
+    }
+
+    fn new(event: TriggerEvent) -> Self {
+        Trigger {
+            event,
+            filter: None,
+            once: false,
+            changed: false,
+            delay: None,
+            throttle: None,
+            from: None,
+            consume: false,
+            queue: None,
+        }
+    }
+
+    /// Only fires when the JavaScript expression is truthy, e.g.
+    /// `key=='Enter'`.
+    pub fn filter(mut self, expression: &'static str) -> Self {
+        self.filter = Some(expression);
+        self
+    }
+
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    pub fn changed(mut self) -> Self {
+        self.changed = true;
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    pub fn throttle(mut self, throttle: Duration) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    pub fn from<T: Into<Target>>(mut self, source: T) -> Self {
+        self.from = Some(source.into());
+        self
+    }
+
+    pub fn consume(mut self) -> Self {
+        self.consume = true;
+        self
+    }
+
+    pub fn queue(mut self, queue: Queue) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+}
+
+impl Queue {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Queue::First => "first",
+            Queue::Last => "last",
+            Queue::All => "all",
+            Queue::None => "none",
+        }
+    }
+}
+
+macro_rules! methods {
+    () => {
+        pub fn hx_get<V: Into<AttrValue>>(self, url: V) -> Self {
+            self.attr("hx-get", url)
+        }
+
+        pub fn hx_post<V: Into<AttrValue>>(self, url: V) -> Self {
+            self.attr("hx-post", url)
+        }
+
+        pub fn hx_put<V: Into<AttrValue>>(self, url: V) -> Self {
+            self.attr("hx-put", url)
+        }
+
+        pub fn hx_patch<V: Into<AttrValue>>(self, url: V) -> Self {
+            self.attr("hx-patch", url)
+        }
+
+        pub fn hx_delete<V: Into<AttrValue>>(self, url: V) -> Self {
+            self.attr("hx-delete", url)
+        }
+
+        pub fn hx_target<T: Into<Target>>(self, target: T) -> Self {
+            self.attr("hx-target", target.into().to_string())
+        }
+
+        pub fn hx_select(self, selector: Selector) -> Self {
+            self.attr("hx-select", selector.0)
+        }
+
+        pub fn hx_indicator<T: Into<Target>>(self, indicator: T) -> Self {
+            self.attr("hx-indicator", indicator.into().to_string())
+        }
+
+        pub fn hx_swap(self, swap: Swap) -> Self {
+            self.attr("hx-swap", swap.as_str())
+        }
+
+        /// Marks the element for an out-of-band swap, see
+        /// [`Markup::render_oob`](crate::Markup::render_oob).
+        pub fn hx_swap_oob(self, swap: Swap) -> Self {
+            self.attr("hx-swap-oob", swap.as_str())
+        }
+
+        pub fn hx_trigger(self, trigger: Trigger) -> Self {
+            self.attr("hx-trigger", trigger.to_string())
+        }
+
+        /// Sets several triggers, any of which issues the request.
+        pub fn hx_triggers<I: IntoIterator<Item = Trigger>>(self, triggers: I) -> Self {
+            let value = triggers
+                .into_iter()
+                .map(|trigger| trigger.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.attr("hx-trigger", value)
+        }
+
+        pub fn hx_push_url(self, push: bool) -> Self {
+            self.attr("hx-push-url", if push { "true" } else { "false" })
+        }
+
+        pub fn hx_boost(self, boost: bool) -> Self {
+            self.attr("hx-boost", if boost { "true" } else { "false" })
+        }
+
+        pub fn hx_confirm<V: Into<AttrValue>>(self, message: V) -> Self {
+            self.attr("hx-confirm", message)
+        }
+
+        /// Sets `hx-vals` to `values` encoded as JSON, which must serialize
+        /// to an object.
+        #[cfg(feature = "serde")]
+        pub fn hx_vals<T: serde::Serialize + ?Sized>(
+            self,
+            values: &T,
+        ) -> Result<Self, serde_json::Error> {
+            Ok(self.attr("hx-vals", vals(values)?))
+        }
+    };
+}
+
+impl RegularTag {
+    methods!();
+}
+
+impl SelfClosingTag {
+    methods!();
+}
+
+#[cfg(feature = "serde")]
+fn vals<T: serde::Serialize + ?Sized>(values: &T) -> Result<String, serde_json::Error> {
+    use serde::ser::Error;
+
+    let value = serde_json::to_value(values)?;
+    if !value.is_object() {
+        return Err(serde_json::Error::custom("hx-vals must be a JSON object"));
+    }
+    serde_json::to_string(&value)
+}
+
+impl From<Selector> for Target {
+    fn from(selector: Selector) -> Self {
+        Target::Selector(selector)
+    }
+}
+
+impl From<Selector> for AttrValue {
+    fn from(selector: Selector) -> Self {
+        AttrValue::Owned(selector.0)
+    }
+}
+
+impl fmt::Display for Swap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::This => f.write_str("this"),
+            Target::Document => f.write_str("document"),
+            Target::Window => f.write_str("window"),
+            Target::Selector(selector) => write!(f, "{selector}"),
+            Target::Closest(selector) => write!(f, "closest {selector}"),
+            Target::Find(selector) => write!(f, "find {selector}"),
+            Target::Next(None) => f.write_str("next"),
+            Target::Next(Some(selector)) => write!(f, "next {selector}"),
+            Target::Previous(None) => f.write_str("previous"),
+            Target::Previous(Some(selector)) => write!(f, "previous {selector}"),
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.event {
+            TriggerEvent::Named(name) => f.write_str(name)?,
+            TriggerEvent::Every(interval) => write!(f, "every {}", duration(interval))?,
+        }
+        if let Some(filter) = self.filter {
+            write!(f, "[{filter}]")?;
+        }
+        if self.once {
+            f.write_str(" once")?;
+        }
+        if self.changed {
+            f.write_str(" changed")?;
+        }
+        if let Some(delay) = self.delay {
+            write!(f, " delay:{}", duration(delay))?;
+        }
+        if let Some(throttle) = self.throttle {
+            write!(f, " throttle:{}", duration(throttle))?;
+        }
+        if let Some(from) = &self.from {
+            write!(f, " from:{from}")?;
+        }
+        if self.consume {
+            f.write_str(" consume")?;
+        }
+        if let Some(queue) = self.queue {
+            write!(f, " queue:{}", queue.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a duration in whole milliseconds, the finest unit htmx reads.
+fn duration(duration: Duration) -> String {
+    format!("{}ms", duration.as_millis())
+}
+
+/// Escapes `value` as a CSS identifier, following `CSS.escape`.
+fn css_ident(value: &str) -> String {
+    let mut ident = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '\0' => ident.push('\u{FFFD}'),
+            '\u{1}'..='\u{1f}' | '\u{7f}' => ident.push_str(&format!("\\{:x} ", c as u32)),
+            '0'..='9' if i == 0 => ident.push_str(&format!("\\{:x} ", c as u32)),
+            '0'..='9' if i == 1 && value.starts_with('-') => {
+                ident.push_str(&format!("\\{:x} ", c as u32))
+            }
+            '-' if i == 0 && value.len() == 1 => ident.push_str("\\-"),
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => ident.push(c),
+            c if !c.is_ascii() => ident.push(c),
+            c => {
+                ident.push('\\');
+                ident.push(c);
+            }
+        }
+    }
+    ident
+}
@@ -0,0 +1,133 @@
+use crate::escape::escape_into;
+use crate::{IntoMarkup, Markup, RenderContext, RenderError};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// A node whose closure writes its output straight into the render buffer,
+/// for content that would be wasteful to build as nodes, such as a large
+/// table or formatted numbers.
+///
+/// The closure runs every time the node is rendered. An error it returns,
+/// such as one from a formatting trait implementation, fails the render with
+/// [`RenderError::Format`].
+#[derive(Clone)]
+pub struct Lazy {
+    write: Arc<dyn Fn(&mut Writer<'_>) -> fmt::Result + Send + Sync>,
+}
+
+/// The output of a [`Lazy`] node. Text written through [`fmt::Write`], such
+/// as with `write!`, is escaped; [`Writer::raw`] is not.
+pub struct Writer<'a> {
+    buffer: &'a mut String,
+    context: &'a RenderContext,
+}
+
+impl Lazy {
+    pub fn new<F>(write: F) -> Self
+    where
+        F: Fn(&mut Writer<'_>) -> fmt::Result + Send + Sync + 'static,
+    {
+        Lazy {
+            write: Arc::new(write),
+        }
+    }
+
+    pub fn into_markup(self) -> Markup {
+        Markup::Lazy(self)
+    }
+
+    pub(crate) fn write_into(
+        &self,
+        buffer: &mut String,
+        context: &RenderContext,
+    ) -> Result<(), RenderError> {
+        let mut writer = Writer { buffer, context };
+        (self.write)(&mut writer).map_err(|_| RenderError::Format)
+    }
+}
+
+impl Writer<'_> {
+    pub fn text(&mut self, text: &str) {
+        escape_into(text, self.buffer);
+    }
+
+    /// Writes `html` as is; it must come from a trusted source.
+    pub fn raw(&mut self, html: &str) {
+        self.buffer.push_str(html);
+    }
+
+    /// The context the node is rendered in, see [`RenderContext`].
+    pub fn context(&self) -> &RenderContext {
+        self.context
+    }
+}
+
+impl fmt::Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.text(s);
+        Ok(())
+    }
+}
+
+impl Markup {
+    /// A [`Lazy`] node running `write` at render time.
+    pub fn lazy<F>(write: F) -> Markup
+    where
+        F: Fn(&mut Writer<'_>) -> fmt::Result + Send + Sync + 'static,
+    {
+        Markup::Lazy(Lazy::new(write))
+    }
+}
+
+impl IntoMarkup for Lazy {
+    fn into_markup(self) -> Markup {
+        Markup::Lazy(self)
+    }
+}
+
+/// Formats the arguments escaped, straight into the node's string. Arguments
+/// without placeholders become a borrowed text node.
+impl IntoMarkup for fmt::Arguments<'_> {
+    fn into_markup(self) -> Markup {
+        if let Some(text) = self.as_str() {
+            return Markup::Text(Cow::Borrowed(text));
+        }
+
+        let mut html = String::new();
+        let mut writer = Writer {
+            buffer: &mut html,
+            context: &RenderContext::default(),
+        };
+        fmt::write(&mut writer, self).expect("a formatting trait implementation returned an error");
+        Markup::Raw(html.into())
+    }
+}
+
+impl fmt::Debug for Lazy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Writer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Writer").finish_non_exhaustive()
+    }
+}
+
+/// Nodes are equal only when they share a closure.
+impl PartialEq for Lazy {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.write, &other.write)
+    }
+}
+
+impl Eq for Lazy {}
+
+impl Hash for Lazy {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.write).cast::<()>().hash(state);
+    }
+}
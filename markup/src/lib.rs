@@ -1,39 +1,126 @@
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "arena")]
+pub mod arena;
+mod attrs;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "http-body")]
+mod body;
+pub mod cache;
+mod chunks;
+mod context;
+pub mod csp;
+pub mod diff;
+mod escape;
+mod fragment;
+mod future;
+pub mod htmx;
+mod lazy;
+mod normalize;
+mod render;
+pub mod scoped;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "sri")]
+pub mod sri;
+pub mod sse;
+pub mod style;
+mod suspense;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod turbo;
+
+#[cfg(feature = "http-body")]
+pub use body::MarkupBody;
+pub use cache::Cached;
+pub use chunks::Chunks;
+pub use context::{Component, Provide, RenderContext};
+pub use diff::{Patch, PatchError};
+pub use future::Async;
+pub use lazy::{Lazy, Writer};
+#[cfg(feature = "rayon")]
+pub use render::PARALLEL_THRESHOLD;
+pub use render::{RenderError, RenderOptions};
+pub use scoped::{Scoped, ScopedStyle};
+pub use style::Style;
+pub use suspense::{Streaming, Suspense};
+
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 pub trait IntoMarkup {
     fn into_markup(self) -> Markup;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Markup {
     Text(Cow<'static, str>),
-    Raw(&'static str),
+    Raw(Cow<'static, str>),
     Fragment(Vec<Markup>),
     RegularTag(RegularTag),
     SelfClosingTag(SelfClosingTag),
+    Scoped(Scoped),
+    /// Marks where the stylesheets of all [`Scoped`] subtrees are emitted.
+    /// Without it they go before `</head>`, or at the start of the output.
+    ScopedStyles,
+    Cached(Cached),
+    /// Resolved by [`Markup::render_async`], see [`Async`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Async(Async),
+    /// A fallback replaced later in the response, see [`Suspense`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Suspense(Suspense),
+    /// Built while rendering, see [`Component`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Component(Component),
+    /// A subtree with its own context values, see [`Markup::provide`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Provide(Provide),
+    /// Writes into the output while rendering, see [`Lazy`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Lazy(Lazy),
     None,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RegularTag {
-    pub tag: &'static str,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::serialize::tag_name")
+    )]
+    pub tag: Cow<'static, str>,
     pub attributes: Vec<Attribute>,
     pub children: Vec<Markup>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelfClosingTag {
-    pub tag: &'static str,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::serialize::tag_name")
+    )]
+    pub tag: Cow<'static, str>,
     pub attributes: Vec<Attribute>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attribute {
-    pub name: &'static str,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::serialize::attribute_name")
+    )]
+    pub name: Cow<'static, str>,
     pub value: AttrValue,
 }
 
-#[derive(Debug)]
+/// Values compare by their text, so `Static("a")` equals `Owned("a".into())`.
+#[derive(Debug, Clone)]
 pub enum AttrValue {
     Static(&'static str),
     Owned(String),
@@ -43,7 +130,7 @@ pub enum AttrValue {
 impl Markup {
     pub fn element(tag: &'static str) -> RegularTag {
         RegularTag {
-            tag,
+            tag: Cow::Borrowed(tag),
             attributes: Vec::new(),
             children: Vec::new(),
         }
@@ -51,101 +138,104 @@ impl Markup {
 
     pub fn self_element(tag: &'static str) -> SelfClosingTag {
         SelfClosingTag {
-            tag,
+            tag: Cow::Borrowed(tag),
             attributes: Vec::new(),
         }
     }
 
     pub fn raw(content: &'static str) -> Self {
-        Markup::Raw(content)
+        Markup::Raw(Cow::Borrowed(content))
+    }
+
+    /// A subtree built and rendered once per `key`, see [`Cached`].
+    pub fn cached<K, F, C>(key: K, build: F) -> Cached
+    where
+        K: Into<Cow<'static, str>>,
+        F: Fn() -> C + Send + Sync + 'static,
+        C: IntoMarkup,
+    {
+        Cached::new(key, build)
     }
 
+    /// Renders the tree with the default options.
+    ///
+    /// # Panics
+    ///
+    /// If the tree holds an unresolved [`Async`] node or a [`Lazy`] node that
+    /// fails, the only errors possible without options;
+    /// [`Markup::render_with`] returns them instead.
     pub fn render(&self) -> String {
-        let mut buffer = String::new();
-        let mut processing: Vec<(&Markup, bool)> = Vec::new();
-
-        processing.push((self, false));
-
-        let render_attr = |attr: &Attribute, buffer: &mut String| match &attr.value {
-            AttrValue::Bool(true) => {
-                buffer.push(' ');
-                buffer.push_str(attr.name);
-            }
-            AttrValue::Bool(false) => {}
-            AttrValue::Static(value) => {
-                buffer.push(' ');
-                buffer.push_str(attr.name);
-                buffer.push_str("=\"");
-                buffer.push_str(&escape_html(value));
-                buffer.push('"');
-            }
-            AttrValue::Owned(value) => {
-                buffer.push(' ');
-                buffer.push_str(attr.name);
-                buffer.push_str("=\"");
-                buffer.push_str(&escape_html(value));
-                buffer.push('"');
-            }
-        };
-
-        while let Some((node, processed)) = processing.pop() {
-            match node {
-                Markup::Text(content) => {
-                    buffer.push_str(&escape_html(content));
-                }
-                Markup::Raw(content) => {
-                    buffer.push_str(content);
-                }
-                Markup::RegularTag(element) => {
-                    if !processed {
-                        buffer.push('<');
-                        buffer.push_str(element.tag);
-
-                        for attr in &element.attributes {
-                            render_attr(&attr, &mut buffer);
-                        }
-
-                        buffer.push('>');
-                        processing.push((node, true));
-
-                        for child in element.children.iter().rev() {
-                            processing.push((child, false));
-                        }
-                    } else {
-                        buffer.push_str("</");
-                        buffer.push_str(element.tag);
-                        buffer.push('>');
-                    }
-                }
-                Markup::SelfClosingTag(element) => {
-                    buffer.push('<');
-                    buffer.push_str(element.tag);
-                    for attr in &element.attributes {
-                        render_attr(&attr, &mut buffer);
-                    }
-                    buffer.push_str(" />");
-                }
-                Markup::Fragment(children) => {
-                    for child in children.iter().rev() {
-                        processing.push((child, false));
-                    }
-                }
-                Markup::None => {}
-            }
+        self.render_with(&RenderOptions::default())
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Appends the rendered tree to `buffer`, which can be cleared and reused
+    /// across renders to avoid reallocating. Panics like [`Markup::render`].
+    pub fn render_into(&self, buffer: &mut String) {
+        if let Err(error) = self.render_into_with(buffer, &RenderOptions::default()) {
+            panic!("{error}");
         }
-        buffer
+    }
+
+    /// Renders into a buffer allocated once from the size hint.
+    pub fn render_with(&self, options: &RenderOptions) -> Result<String, RenderError> {
+        let mut buffer = String::with_capacity(render::size_hint(self, options));
+        self.render_into_with(&mut buffer, options)?;
+        Ok(buffer)
+    }
+
+    /// Like [`Markup::render_into`]; on error `buffer` is left unchanged.
+    pub fn render_into_with(
+        &self,
+        buffer: &mut String,
+        options: &RenderOptions,
+    ) -> Result<(), RenderError> {
+        render::render_into(self, buffer, options)
+    }
+
+    /// Estimated length of [`Markup::render`]'s output, used to allocate the
+    /// output buffer once. [`Cached`] subtrees only count once they are in
+    /// the cache, and [`Component`], [`Lazy`] and [`Async`] nodes count as
+    /// empty, so the output can be longer.
+    pub fn size_hint(&self) -> usize {
+        render::size_hint(self, &RenderOptions::default())
     }
 }
 
 impl RegularTag {
+    /// Sets an attribute. `class` and `style` values are merged with any
+    /// previous value; other duplicates replace the earlier attribute.
     pub fn attr<V: Into<AttrValue>>(mut self, name: &'static str, value: V) -> Self {
-        self.attributes.push(Attribute {
-            name,
-            value: value.into(),
-        });
+        attrs::set(&mut self.attributes, name, value.into());
         self
     }
 
+    pub fn id<V: Into<AttrValue>>(self, id: V) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn class<V: Into<AttrValue>>(self, class: V) -> Self {
+        self.attr("class", class)
+    }
+
+    pub fn classes<I, V>(self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<AttrValue>,
+    {
+        classes
+            .into_iter()
+            .fold(self, |tag, class| tag.class(class))
+    }
+
+    pub fn class_if<V: Into<AttrValue>>(self, condition: bool, class: V) -> Self {
+        if condition { self.class(class) } else { self }
+    }
+
+    pub fn style<V: Into<AttrValue>>(self, style: V) -> Self {
+        self.attr("style", style)
+    }
+
     pub fn child<C: IntoMarkup>(mut self, child: C) -> Self {
         self.children.push(child.into_markup());
         self
@@ -157,14 +247,38 @@ impl RegularTag {
 }
 
 impl SelfClosingTag {
+    /// Sets an attribute, merging duplicates like [`RegularTag::attr`].
     pub fn attr<V: Into<AttrValue>>(mut self, name: &'static str, value: V) -> Self {
-        self.attributes.push(Attribute {
-            name,
-            value: value.into(),
-        });
+        attrs::set(&mut self.attributes, name, value.into());
         self
     }
 
+    pub fn id<V: Into<AttrValue>>(self, id: V) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn class<V: Into<AttrValue>>(self, class: V) -> Self {
+        self.attr("class", class)
+    }
+
+    pub fn classes<I, V>(self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<AttrValue>,
+    {
+        classes
+            .into_iter()
+            .fold(self, |tag, class| tag.class(class))
+    }
+
+    pub fn class_if<V: Into<AttrValue>>(self, condition: bool, class: V) -> Self {
+        if condition { self.class(class) } else { self }
+    }
+
+    pub fn style<V: Into<AttrValue>>(self, style: V) -> Self {
+        self.attr("style", style)
+    }
+
     pub fn into_markup(self) -> Markup {
         Markup::SelfClosingTag(self)
     }
@@ -228,6 +342,38 @@ where
     }
 }
 
+impl AttrValue {
+    /// Returns the textual value, or `None` for boolean attributes.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Static(value) => Some(value),
+            AttrValue::Owned(value) => Some(value),
+            AttrValue::Bool(_) => None,
+        }
+    }
+}
+
+impl PartialEq for AttrValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AttrValue::Bool(a), AttrValue::Bool(b)) => a == b,
+            _ => self.as_str().is_some() && self.as_str() == other.as_str(),
+        }
+    }
+}
+
+impl Eq for AttrValue {}
+
+impl Hash for AttrValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            AttrValue::Bool(value) => value.hash(state),
+            AttrValue::Static(value) => value.hash(state),
+            AttrValue::Owned(value) => value.hash(state),
+        }
+    }
+}
+
 impl From<&'static str> for AttrValue {
     fn from(value: &'static str) -> Self {
         AttrValue::Static(value)
@@ -245,46 +391,3 @@ impl From<bool> for AttrValue {
         AttrValue::Bool(value)
     }
 }
-
-/// Escapes HTML special characters in a string.
-fn escape_html(s: &str) -> Cow<'_, str> {
-    let mut needs_escaping = false;
-    let mut additional_len = 0;
-    let mut iter = s.chars().peekable();
-
-    while let Some(c) = iter.next() {
-        match c {
-            '&' => {
-                needs_escaping = true;
-                additional_len += 4;
-            }
-            '<' | '>' => {
-                needs_escaping = true;
-                additional_len += 3;
-            }
-            '"' | '\'' => {
-                needs_escaping = true;
-                additional_len += 5;
-            }
-            _ => {}
-        }
-    }
-
-    if !needs_escaping {
-        return Cow::Borrowed(s);
-    }
-
-    let mut output = String::with_capacity(s.len() + additional_len);
-    for c in s.chars() {
-        match c {
-            '&' => output.push_str("&amp;"),
-            '<' => output.push_str("&lt;"),
-            '>' => output.push_str("&gt;"),
-            '"' => output.push_str("&quot;"),
-            '\'' => output.push_str("&apos;"),
-            _ => output.push(c),
-        }
-    }
-
-    Cow::Owned(output)
-}
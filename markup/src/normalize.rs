@@ -0,0 +1,72 @@
+use crate::{AttrValue, Attribute, Markup, RegularTag, SelfClosingTag};
+
+impl Markup {
+    /// Compares two trees by what they mean rather than how they were built:
+    /// attribute order is ignored, fragments and `None` are flattened away,
+    /// and adjacent text nodes are merged.
+    pub fn semantic_eq(&self, other: &Markup) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    /// Returns the canonical form used by [`Markup::semantic_eq`].
+    pub fn normalized(&self) -> Markup {
+        let mut children = normalize_children(std::slice::from_ref(self));
+        match children.len() {
+            0 => Markup::None,
+            1 => children.pop().unwrap(),
+            _ => Markup::Fragment(children),
+        }
+    }
+}
+
+fn normalize_children(nodes: &[Markup]) -> Vec<Markup> {
+    let mut output = Vec::with_capacity(nodes.len());
+    let mut processing: Vec<&Markup> = nodes.iter().rev().collect();
+
+    while let Some(node) = processing.pop() {
+        let normalized = match node {
+            Markup::Fragment(children) => {
+                processing.extend(children.iter().rev());
+                continue;
+            }
+            Markup::None => continue,
+            Markup::Text(content) if content.is_empty() => continue,
+            Markup::Text(content) => {
+                if let Some(Markup::Text(previous)) = output.last_mut() {
+                    previous.to_mut().push_str(content);
+                    continue;
+                }
+                Markup::Text(content.clone())
+            }
+            Markup::RegularTag(element) => Markup::RegularTag(RegularTag {
+                tag: element.tag.clone(),
+                attributes: normalize_attributes(&element.attributes),
+                children: normalize_children(&element.children),
+            }),
+            Markup::SelfClosingTag(element) => Markup::SelfClosingTag(SelfClosingTag {
+                tag: element.tag.clone(),
+                attributes: normalize_attributes(&element.attributes),
+            }),
+            Markup::Scoped(scoped) => {
+                let mut scoped = scoped.clone();
+                scoped.content = Box::new(scoped.content.normalized());
+                Markup::Scoped(scoped)
+            }
+            other => other.clone(),
+        };
+        output.push(normalized);
+    }
+
+    output
+}
+
+/// Sorts attributes by name, dropping `false` booleans which never render.
+fn normalize_attributes(attributes: &[Attribute]) -> Vec<Attribute> {
+    let mut attributes: Vec<Attribute> = attributes
+        .iter()
+        .filter(|attr| !matches!(attr.value, AttrValue::Bool(false)))
+        .cloned()
+        .collect();
+    attributes.sort_by(|a, b| a.name.cmp(&b.name));
+    attributes
+}
@@ -0,0 +1,679 @@
+use crate::cache::{self, Cached, FragmentCache};
+use crate::csp::{self, InvalidNonce};
+use crate::escape::{escape_into, escaped_len};
+use crate::{AttrValue, Attribute, Component, Lazy, Markup, RenderContext};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+/// Options applied while rendering a tree.
+#[derive(Default, Clone)]
+pub struct RenderOptions {
+    nonce: Option<String>,
+    cache: Option<Arc<dyn FragmentCache>>,
+    pub(crate) context: RenderContext,
+    #[cfg(feature = "rayon")]
+    parallel_threshold: Option<usize>,
+}
+
+/// Default length from which child lists are rendered in parallel.
+#[cfg(feature = "rayon")]
+pub const PARALLEL_THRESHOLD: usize = 1024;
+
+#[derive(Debug, PartialEq)]
+pub enum RenderError {
+    /// An `on*` attribute was found while a CSP nonce is active; inline
+    /// handlers cannot carry a nonce and would be blocked by the browser.
+    InlineEventHandler { tag: String, attribute: String },
+    /// An [`Async`](crate::Async) node reached a synchronous renderer; the
+    /// tree must be resolved first, see [`Markup::render_async`].
+    Unresolved,
+    /// A [`Lazy`] node returned an error, which with `write!` means a
+    /// formatting trait implementation failed.
+    Format,
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        RenderOptions::default()
+    }
+
+    /// Sets the per-request CSP nonce added to every `<script>`, `<style>`
+    /// and stylesheet `<link>` element. Fails unless the nonce is base64, as
+    /// it ends up in the `Content-Security-Policy` header.
+    pub fn nonce<S: Into<String>>(mut self, nonce: S) -> Result<Self, InvalidNonce> {
+        let nonce = nonce.into();
+        if !csp::is_valid_nonce(&nonce) {
+            return Err(InvalidNonce(nonce));
+        }
+        self.nonce = Some(nonce);
+        Ok(self)
+    }
+
+    /// Stores [`Cached`] subtrees in `cache` instead of [`cache::global`].
+    pub fn cache(mut self, cache: Arc<dyn FragmentCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Makes `context` available to [`Component`] nodes.
+    pub fn context(mut self, context: RenderContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Child lists at least this long are rendered in parallel; defaults to
+    /// [`PARALLEL_THRESHOLD`].
+    #[cfg(feature = "rayon")]
+    pub fn parallel_threshold(mut self, threshold: usize) -> Self {
+        self.parallel_threshold = Some(threshold.max(2));
+        self
+    }
+
+    fn fragment_cache(&self) -> &dyn FragmentCache {
+        match &self.cache {
+            Some(cache) => cache.as_ref(),
+            None => cache::global(),
+        }
+    }
+
+    /// The `Content-Security-Policy` header value matching the nonce.
+    pub fn content_security_policy(&self) -> Option<String> {
+        self.nonce
+            .as_deref()
+            .map(|nonce| csp::header_value(nonce).expect("nonce is checked when set"))
+    }
+}
+
+impl fmt::Debug for RenderOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderOptions")
+            .field("nonce", &self.nonce)
+            .field("cache", &self.cache.as_ref().map(|_| "FragmentCache"))
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::InlineEventHandler { tag, attribute } => write!(
+                f,
+                "inline event handler `{attribute}` on <{tag}> is not allowed under a CSP nonce"
+            ),
+            RenderError::Unresolved => f.write_str(
+                "async component rendered before being resolved; use `Markup::render_async`",
+            ),
+            RenderError::Format => f.write_str("a lazy node returned a formatting error"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// A borrowed view of one node, letting the renderer walk any tree type
+/// that can describe itself in these terms.
+pub(crate) enum View<'a, N, A> {
+    Text(&'a str),
+    Raw(&'a str),
+    Element {
+        tag: &'a str,
+        attributes: &'a [A],
+        children: &'a [N],
+    },
+    Void {
+        tag: &'a str,
+        attributes: &'a [A],
+    },
+    Fragment(&'a [N]),
+    Scoped(&'a str, &'a N),
+    ScopedStyles,
+    Cached(&'a Cached),
+    Component(&'a Component),
+    Provide(&'a RenderContext, &'a N),
+    Lazy(&'a Lazy),
+    /// An [`Async`](crate::Async) node, which cannot be rendered.
+    Unresolved,
+    None,
+}
+
+pub(crate) enum Value<'a> {
+    Str(&'a str),
+    Bool(bool),
+}
+
+pub(crate) trait Node: Sized + Sync {
+    type Attr: Attr;
+
+    fn view(&self) -> View<'_, Self, Self::Attr>;
+}
+
+pub(crate) trait Attr: Sync {
+    fn name(&self) -> &str;
+
+    fn value(&self) -> Value<'_>;
+}
+
+impl Node for Markup {
+    type Attr = Attribute;
+
+    fn view(&self) -> View<'_, Self, Attribute> {
+        match self {
+            Markup::Text(content) => View::Text(content),
+            Markup::Raw(content) => View::Raw(content),
+            Markup::RegularTag(element) => View::Element {
+                tag: &element.tag,
+                attributes: &element.attributes,
+                children: &element.children,
+            },
+            Markup::SelfClosingTag(element) => View::Void {
+                tag: &element.tag,
+                attributes: &element.attributes,
+            },
+            Markup::Fragment(children) => View::Fragment(children),
+            Markup::Scoped(scoped) => View::Scoped(&scoped.css, &scoped.content),
+            Markup::ScopedStyles => View::ScopedStyles,
+            Markup::Cached(cached) => View::Cached(cached),
+            Markup::Async(_) => View::Unresolved,
+            Markup::Suspense(suspense) => View::Fragment(std::slice::from_ref(&suspense.fallback)),
+            Markup::Component(component) => View::Component(component),
+            Markup::Provide(provide) => View::Provide(&provide.context, &provide.content),
+            Markup::Lazy(lazy) => View::Lazy(lazy),
+            Markup::None => View::None,
+        }
+    }
+}
+
+impl Attr for Attribute {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Value<'_> {
+        match &self.value {
+            AttrValue::Static(value) => Value::Str(value),
+            AttrValue::Owned(value) => Value::Str(value),
+            AttrValue::Bool(value) => Value::Bool(*value),
+        }
+    }
+}
+
+pub(crate) fn render_into<N: Node>(
+    root: &N,
+    buffer: &mut String,
+    options: &RenderOptions,
+) -> Result<(), RenderError> {
+    let start = buffer.len();
+    Renderer::new(root, options)
+        .run(buffer)
+        .inspect_err(|_| buffer.truncate(start))
+}
+
+/// Renderer state produced by [`Renderer::partial`].
+#[cfg(feature = "rayon")]
+pub(crate) struct Partial<'a> {
+    styles: Vec<&'a str>,
+    styles_at: Option<usize>,
+    head_end: Option<usize>,
+}
+
+/// Walks a tree with an explicit stack and writes it into a buffer,
+/// collecting the scoped stylesheets used along the way.
+pub(crate) struct Renderer<'a, N> {
+    options: &'a RenderOptions,
+    processing: Vec<(&'a N, bool)>,
+    /// Contexts of the enclosing [`View::Provide`] nodes, innermost last.
+    contexts: Vec<RenderContext>,
+    styles: Vec<&'a str>,
+    styles_at: Option<usize>,
+    head_end: Option<usize>,
+}
+
+impl<'a, N: Node> Renderer<'a, N> {
+    pub(crate) fn new(root: &'a N, options: &'a RenderOptions) -> Self {
+        Renderer {
+            options,
+            processing: vec![(root, false)],
+            contexts: Vec::new(),
+            styles: Vec::new(),
+            styles_at: None,
+            head_end: None,
+        }
+    }
+
+    pub(crate) fn run(mut self, buffer: &mut String) -> Result<(), RenderError> {
+        let start = buffer.len();
+        self.walk(buffer)?;
+
+        if !self.styles.is_empty() {
+            let at = self.styles_at.or(self.head_end).unwrap_or(start);
+            buffer.insert_str(at, &style_block(&self.styles, self.options));
+        }
+
+        Ok(())
+    }
+
+    fn walk(&mut self, buffer: &mut String) -> Result<(), RenderError> {
+        while let Some((node, processed)) = self.processing.pop() {
+            match node.view() {
+                View::Text(content) => {
+                    escape_into(content, buffer);
+                }
+                View::Raw(content) => {
+                    buffer.push_str(content);
+                }
+                View::Element {
+                    tag,
+                    attributes,
+                    children,
+                } => {
+                    if !processed {
+                        open_tag(self.options, tag, attributes, buffer)?;
+                        buffer.push('>');
+                        self.processing.push((node, true));
+                        self.push_children(children, buffer)?;
+                    } else {
+                        if tag.eq_ignore_ascii_case("head") && self.head_end.is_none() {
+                            self.head_end = Some(buffer.len());
+                        }
+                        buffer.push_str("</");
+                        buffer.push_str(tag);
+                        buffer.push('>');
+                    }
+                }
+                View::Void { tag, attributes } => {
+                    open_tag(self.options, tag, attributes, buffer)?;
+                    buffer.push_str(" />");
+                }
+                View::Fragment(children) => {
+                    self.push_children(children, buffer)?;
+                }
+                View::Scoped(css, content) => {
+                    self.use_style(css);
+                    self.processing.push((content, false));
+                }
+                View::ScopedStyles => {
+                    self.styles_at.get_or_insert(buffer.len());
+                }
+                View::Cached(cached) => {
+                    render_cached(self.options, self.context(), cached, buffer)?;
+                }
+                View::Component(component) => {
+                    render_component(self.options, self.context(), component, buffer)?;
+                }
+                View::Lazy(lazy) => {
+                    lazy.write_into(buffer, self.context())?;
+                }
+                View::Provide(context, content) => {
+                    if !processed {
+                        self.contexts.push(self.context().merged(context));
+                        self.processing.push((node, true));
+                        self.processing.push((content, false));
+                    } else {
+                        self.contexts.pop();
+                    }
+                }
+                View::Unresolved => return Err(RenderError::Unresolved),
+                View::None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn context(&self) -> &RenderContext {
+        self.contexts.last().unwrap_or(&self.options.context)
+    }
+
+    fn use_style(&mut self, css: &'a str) {
+        if !self.styles.contains(&css) {
+            self.styles.push(css);
+        }
+    }
+
+    /// Queues `children` to be rendered next. With the `rayon` feature, long
+    /// lists are instead rendered right away across threads.
+    #[cfg(not(feature = "rayon"))]
+    fn push_children(&mut self, children: &'a [N], _: &mut String) -> Result<(), RenderError> {
+        for child in children.iter().rev() {
+            self.processing.push((child, false));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn push_children(&mut self, children: &'a [N], buffer: &mut String) -> Result<(), RenderError> {
+        let threshold = self
+            .options
+            .parallel_threshold
+            .unwrap_or(PARALLEL_THRESHOLD);
+        if children.len() < threshold {
+            for child in children.iter().rev() {
+                self.processing.push((child, false));
+            }
+            return Ok(());
+        }
+
+        use rayon::prelude::*;
+
+        let options = self.options;
+        let context = self.context();
+        let chunk_size = children
+            .len()
+            .div_ceil(rayon::current_num_threads() * 4)
+            .max(64);
+        let chunks: Vec<_> = children
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut html = String::new();
+                Renderer::partial(chunk, options, context, &mut html).map(|partial| (html, partial))
+            })
+            .collect();
+
+        for chunk in chunks {
+            let (html, partial) = chunk?;
+            self.merge(partial, buffer.len());
+            buffer.push_str(&html);
+        }
+        Ok(())
+    }
+
+    /// Renders `nodes` in order without emitting the stylesheet block, for
+    /// merging into a parent renderer.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn partial(
+        nodes: &'a [N],
+        options: &'a RenderOptions,
+        context: &RenderContext,
+        buffer: &mut String,
+    ) -> Result<Partial<'a>, RenderError> {
+        let mut renderer = Renderer {
+            options,
+            processing: nodes.iter().rev().map(|node| (node, false)).collect(),
+            contexts: vec![context.clone()],
+            styles: Vec::new(),
+            styles_at: None,
+            head_end: None,
+        };
+        renderer.walk(buffer)?;
+
+        Ok(Partial {
+            styles: renderer.styles,
+            styles_at: renderer.styles_at,
+            head_end: renderer.head_end,
+        })
+    }
+
+    /// Folds in the state of a partial render whose output was appended to
+    /// the buffer at `offset`.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn merge(&mut self, partial: Partial<'a>, offset: usize) {
+        for css in partial.styles {
+            self.use_style(css);
+        }
+        if self.styles_at.is_none() {
+            self.styles_at = partial.styles_at.map(|at| offset + at);
+        }
+        if self.head_end.is_none() {
+            self.head_end = partial.head_end.map(|at| offset + at);
+        }
+    }
+}
+
+/// Serves a cached subtree in `context`, building and rendering it on a miss.
+///
+/// The fragment is rendered on its own, so scoped stylesheets inside it are
+/// emitted at its start. The cache is bypassed under a CSP nonce, as the
+/// stored HTML would carry another request's nonce, and for subtrees that
+/// read the context, as their output is not determined by the key alone.
+pub(crate) fn render_cached(
+    options: &RenderOptions,
+    context: &RenderContext,
+    cached: &Cached,
+    buffer: &mut String,
+) -> Result<(), RenderError> {
+    let options = &in_context(options, context);
+    if options.nonce.is_some() {
+        return render_into(&cached.build(), buffer, options);
+    }
+
+    let cache = options.fragment_cache();
+    if let Some(html) = cache.get(&cached.key) {
+        buffer.push_str(&html);
+        return Ok(());
+    }
+
+    let markup = cached.build();
+    if markup.reads_context() {
+        return render_into(&markup, buffer, options);
+    }
+    let mut html = String::new();
+    render_into(&markup, &mut html, options)?;
+    buffer.push_str(&html);
+    cache.insert(&cached.key, html.into(), cached.ttl);
+    Ok(())
+}
+
+/// Builds a component from `context` and renders the result on its own.
+pub(crate) fn render_component(
+    options: &RenderOptions,
+    context: &RenderContext,
+    component: &Component,
+    buffer: &mut String,
+) -> Result<(), RenderError> {
+    let markup = component.build(context);
+    render_into(&markup, buffer, &in_context(options, context))
+}
+
+fn in_context<'a>(options: &'a RenderOptions, context: &RenderContext) -> Cow<'a, RenderOptions> {
+    if *context == options.context {
+        return Cow::Borrowed(options);
+    }
+
+    Cow::Owned(RenderOptions {
+        context: context.clone(),
+        ..options.clone()
+    })
+}
+
+/// Writes `<tag` and its attributes, adding the CSP nonce where needed.
+pub(crate) fn open_tag<A: Attr>(
+    options: &RenderOptions,
+    tag: &str,
+    attributes: &[A],
+    buffer: &mut String,
+) -> Result<(), RenderError> {
+    let csp = options.nonce.is_some();
+    if csp && let Some(attr) = attributes.iter().find(|attr| is_event_handler(*attr)) {
+        return Err(RenderError::InlineEventHandler {
+            tag: tag.to_string(),
+            attribute: attr.name().to_string(),
+        });
+    }
+
+    buffer.push('<');
+    buffer.push_str(tag);
+
+    // A nonce set by hand is replaced by the one for this response.
+    let nonce = csp && needs_nonce(tag, attributes);
+    for attr in attributes {
+        if nonce && attr.name().eq_ignore_ascii_case("nonce") {
+            continue;
+        }
+        render_attr(attr, buffer);
+    }
+
+    if nonce {
+        render_nonce(options, buffer);
+    }
+
+    Ok(())
+}
+
+/// The `<style>` element holding every collected scoped stylesheet.
+pub(crate) fn style_block(styles: &[&str], options: &RenderOptions) -> String {
+    let mut block = String::from("<style");
+    render_nonce(options, &mut block);
+    block.push('>');
+    for css in styles {
+        block.push_str(&css.replace("</", "<\\/"));
+    }
+    block.push_str("</style>");
+    block
+}
+
+pub(crate) fn render_nonce(options: &RenderOptions, buffer: &mut String) {
+    if let Some(nonce) = &options.nonce {
+        buffer.push_str(" nonce=\"");
+        escape_into(nonce, buffer);
+        buffer.push('"');
+    }
+}
+
+/// Scripts, inline or not, need a nonce under `'strict-dynamic'`, and so do
+/// style elements and stylesheet links.
+fn needs_nonce<A: Attr>(tag: &str, attributes: &[A]) -> bool {
+    tag.eq_ignore_ascii_case("script")
+        || tag.eq_ignore_ascii_case("style")
+        || (tag.eq_ignore_ascii_case("link") && is_stylesheet(attributes))
+}
+
+fn is_stylesheet<A: Attr>(attributes: &[A]) -> bool {
+    attributes.iter().any(|attr| {
+        attr.name().eq_ignore_ascii_case("rel")
+            && matches!(attr.value(), Value::Str(rel) if rel
+                .split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("stylesheet")))
+    })
+}
+
+fn is_event_handler<A: Attr>(attr: &A) -> bool {
+    let name = attr.name().as_bytes();
+    name.len() > 2
+        && name[..2].eq_ignore_ascii_case(b"on")
+        && !matches!(attr.value(), Value::Bool(false))
+}
+
+/// The scoped stylesheets rendering `root` collects, and whether the tree has
+/// a placeholder or a `<head>` to put them in. Found without rendering, for
+/// output that is written out before the walk is over.
+pub(crate) struct StylePlan<'a> {
+    pub(crate) styles: Vec<&'a str>,
+    pub(crate) placeholder: bool,
+    pub(crate) head: bool,
+}
+
+pub(crate) fn plan_styles<N: Node>(root: &N) -> StylePlan<'_> {
+    let mut plan = StylePlan {
+        styles: Vec::new(),
+        placeholder: false,
+        head: false,
+    };
+    let mut processing = vec![root];
+
+    while let Some(node) = processing.pop() {
+        match node.view() {
+            View::Element { tag, children, .. } => {
+                plan.head |= tag.eq_ignore_ascii_case("head");
+                processing.extend(children.iter().rev());
+            }
+            View::Fragment(children) => processing.extend(children.iter().rev()),
+            View::Scoped(css, content) => {
+                if !plan.styles.contains(&css) {
+                    plan.styles.push(css);
+                }
+                processing.push(content);
+            }
+            View::ScopedStyles => plan.placeholder = true,
+            View::Provide(_, content) => processing.push(content),
+            _ => {}
+        }
+    }
+
+    plan
+}
+
+/// Estimated rendered length of `root` under `options`; content only known
+/// while rendering counts as empty.
+pub(crate) fn size_hint<N: Node>(root: &N, options: &RenderOptions) -> usize {
+    let nonce_len = options
+        .nonce
+        .as_deref()
+        .map_or(0, |nonce| " nonce=\"\"".len() + escaped_len(nonce));
+    let attrs_len = |tag: &str, attributes: &[N::Attr]| -> usize {
+        let nonce_len = if needs_nonce(tag, attributes) {
+            nonce_len
+        } else {
+            0
+        };
+        attributes.iter().map(attr_len).sum::<usize>() + nonce_len
+    };
+
+    let mut len = 0;
+    let mut processing = vec![root];
+
+    while let Some(node) = processing.pop() {
+        len += match node.view() {
+            View::Text(content) => escaped_len(content),
+            View::Raw(content) => content.len(),
+            View::Element {
+                tag,
+                attributes,
+                children,
+            } => {
+                processing.extend(children);
+                "<></>".len() + 2 * tag.len() + attrs_len(tag, attributes)
+            }
+            View::Void { tag, attributes } => "< />".len() + tag.len() + attrs_len(tag, attributes),
+            View::Fragment(children) => {
+                processing.extend(children);
+                0
+            }
+            View::Scoped(css, content) => {
+                processing.push(content);
+                "<style></style>".len() + nonce_len + css.len() + css.matches("</").count()
+            }
+            View::Cached(cached) => options
+                .fragment_cache()
+                .get(&cached.key)
+                .map_or(0, |html| html.len()),
+            View::Provide(_, content) => {
+                processing.push(content);
+                0
+            }
+            View::ScopedStyles
+            | View::Component(_)
+            | View::Lazy(_)
+            | View::Unresolved
+            | View::None => 0,
+        };
+    }
+
+    len
+}
+
+fn attr_len<A: Attr>(attr: &A) -> usize {
+    match attr.value() {
+        Value::Bool(true) => 1 + attr.name().len(),
+        Value::Bool(false) => 0,
+        Value::Str(value) => " =\"\"".len() + attr.name().len() + escaped_len(value),
+    }
+}
+
+fn render_attr<A: Attr>(attr: &A, buffer: &mut String) {
+    match attr.value() {
+        Value::Bool(true) => {
+            buffer.push(' ');
+            buffer.push_str(attr.name());
+        }
+        Value::Bool(false) => {}
+        Value::Str(value) => {
+            buffer.push(' ');
+            buffer.push_str(attr.name());
+            buffer.push_str("=\"");
+            escape_into(value, buffer);
+            buffer.push('"');
+        }
+    }
+}
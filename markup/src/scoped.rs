@@ -0,0 +1,241 @@
+use crate::{Markup, RegularTag};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// A component stylesheet whose selectors are confined to the component.
+///
+/// Every selector is rewritten to live under a class derived from a hash of
+/// the stylesheet, e.g. `.title` becomes `.card-1f2e3d4c .title`. `&` refers
+/// to the component root itself, so `&:hover` becomes `.card-1f2e3d4c:hover`.
+#[derive(Debug)]
+pub struct ScopedStyle {
+    name: &'static str,
+    source: &'static str,
+    class: OnceLock<String>,
+    css: OnceLock<String>,
+}
+
+/// A subtree whose root carries a scope class, along with the rewritten
+/// stylesheet for that class.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scoped {
+    pub css: Cow<'static, str>,
+    pub content: Box<Markup>,
+}
+
+impl ScopedStyle {
+    pub const fn new(name: &'static str, source: &'static str) -> Self {
+        ScopedStyle {
+            name,
+            source,
+            class: OnceLock::new(),
+            css: OnceLock::new(),
+        }
+    }
+
+    /// The hashed scope class, e.g. `card-1f2e3d4c`.
+    pub fn class(&'static self) -> &'static str {
+        self.class
+            .get_or_init(|| format!("{}-{:08x}", self.name, fnv1a(self.source.as_bytes())))
+    }
+
+    /// The stylesheet with every selector rewritten under the scope class.
+    pub fn css(&'static self) -> &'static str {
+        self.css.get_or_init(|| {
+            let mut output = String::with_capacity(self.source.len() * 2);
+            rewrite_rules(&strip_comments(self.source), self.class(), &mut output);
+            output
+        })
+    }
+
+    /// Adds the scope class to `root` and registers the stylesheet for
+    /// collection when the tree is rendered.
+    pub fn scope(&'static self, root: RegularTag) -> Markup {
+        Markup::Scoped(Scoped {
+            css: Cow::Borrowed(self.css()),
+            content: Box::new(root.class(self.class()).into_markup()),
+        })
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Removes `/* */` comments, leaving strings that contain `/*` alone.
+fn strip_comments(css: &str) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut quote: Option<u8> = None;
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < css.len() {
+        let byte = css.as_bytes()[i];
+        match (quote, byte) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'/') if css[i..].starts_with("/*") => {
+                output.push_str(&css[copied..i]);
+                i = css[i + 2..]
+                    .find("*/")
+                    .map_or(css.len(), |end| i + 2 + end + 2);
+                copied = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    output.push_str(&css[copied..]);
+    output
+}
+
+/// Rewrites a list of rules, recursing into grouping at-rules.
+fn rewrite_rules(css: &str, class: &str, output: &mut String) {
+    let mut rest = css.trim_start();
+
+    loop {
+        // Statements such as `@import` and `@layer a, b;` end at a `;` and
+        // have no block; they are kept as they are.
+        if rest.starts_with('@')
+            && let Some(end) = find_outside(rest, b';')
+            && find_outside(rest, b'{').is_none_or(|open| end < open)
+        {
+            output.push_str(rest[..=end].trim());
+            rest = rest[end + 1..].trim_start();
+            continue;
+        }
+
+        let Some(open) = find_outside(rest, b'{') else {
+            break;
+        };
+        let prelude = rest[..open].trim();
+        let close = matching_brace(rest, open);
+        let body = &rest[open + 1..close];
+        rest = rest.get(close + 1..).unwrap_or("").trim_start();
+
+        if let Some(at_rule) = prelude.strip_prefix('@') {
+            output.push_str(prelude);
+            output.push('{');
+            if ["media", "supports", "container", "layer"]
+                .iter()
+                .any(|name| at_rule.starts_with(name))
+            {
+                rewrite_rules(body, class, output);
+            } else {
+                output.push_str(body.trim());
+            }
+            output.push('}');
+            continue;
+        }
+
+        for (i, selector) in split_outside(prelude, b',').enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+            rewrite_selector(selector.trim(), class, output);
+        }
+        output.push('{');
+        output.push_str(body.trim());
+        output.push('}');
+    }
+}
+
+/// Replaces the nesting selector `&` with the scope class, or prefixes the
+/// selector with it. An `&` in a string or attribute selector is kept.
+fn rewrite_selector(selector: &str, class: &str, output: &mut String) {
+    let mut nesting = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut brackets = 0usize;
+    for (i, byte) in selector.bytes().enumerate() {
+        match (quote, byte) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'[') => brackets += 1,
+            (None, b']') => brackets = brackets.saturating_sub(1),
+            (None, b'&') if brackets == 0 => nesting.push(i),
+            _ => {}
+        }
+    }
+
+    if nesting.is_empty() {
+        output.push('.');
+        output.push_str(class);
+        output.push(' ');
+        output.push_str(selector);
+        return;
+    }
+    let mut copied = 0;
+    for i in nesting {
+        output.push_str(&selector[copied..i]);
+        output.push('.');
+        output.push_str(class);
+        copied = i + 1;
+    }
+    output.push_str(&selector[copied..]);
+}
+
+fn find_outside(css: &str, target: u8) -> Option<usize> {
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+
+    for (i, byte) in css.bytes().enumerate() {
+        match (quote, byte) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b) if b == target && depth == 0 => return Some(i),
+            (None, b'(' | b'[') => depth += 1,
+            (None, b')' | b']') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn split_outside(css: &str, separator: u8) -> impl Iterator<Item = &str> {
+    let mut rest = Some(css);
+    std::iter::from_fn(move || {
+        let current = rest?;
+        match find_outside(current, separator) {
+            Some(i) => {
+                rest = Some(&current[i + 1..]);
+                Some(&current[..i])
+            }
+            None => {
+                rest = None;
+                Some(current)
+            }
+        }
+    })
+}
+
+/// Finds the `}` closing the block opened at `open`, or the end of input.
+fn matching_brace(css: &str, open: usize) -> usize {
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+
+    for (i, byte) in css.bytes().enumerate().skip(open) {
+        match (quote, byte) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'{') => depth += 1,
+            (None, b'}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    css.len()
+}
@@ -0,0 +1,126 @@
+//! Serde support. Trees serialize to plain JSON-friendly data; borrowed
+//! tags, names and text come back owned.
+//!
+//! Tag and attribute names are written out unescaped, so deserialized names
+//! are checked to hold only characters HTML allows in them.
+
+use crate::{AttrValue, Cached, Markup};
+use serde::de::{self, Deserializer, Unexpected, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+/// Attribute values are a JSON string or boolean.
+impl Serialize for AttrValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AttrValue::Static(value) => serializer.serialize_str(value),
+            AttrValue::Owned(value) => serializer.serialize_str(value),
+            AttrValue::Bool(value) => serializer.serialize_bool(*value),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AttrValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AttrValueVisitor;
+
+        impl Visitor<'_> for AttrValueVisitor {
+            type Value = AttrValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string or a boolean")
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<AttrValue, E> {
+                Ok(AttrValue::Bool(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<AttrValue, E> {
+                Ok(AttrValue::Owned(value.to_string()))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<AttrValue, E> {
+                Ok(AttrValue::Owned(value))
+            }
+        }
+
+        deserializer.deserialize_any(AttrValueVisitor)
+    }
+}
+
+/// A cached node serializes the tree its builder produces; on the way back
+/// the builder clones that tree.
+impl Serialize for Cached {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Cached", 3)?;
+        state.serialize_field("key", &self.key)?;
+        state.serialize_field("ttl_ms", &self.ttl.map(|ttl| ttl.as_millis() as u64))?;
+        state.serialize_field("content", &self.build())?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Cached {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct CachedData {
+            key: Cow<'static, str>,
+            ttl_ms: Option<u64>,
+            content: Markup,
+        }
+
+        let data = CachedData::deserialize(deserializer)?;
+        let content = data.content;
+        let mut cached = Cached::new(data.key, move || content.clone());
+        cached.ttl = data.ttl_ms.map(Duration::from_millis);
+        Ok(cached)
+    }
+}
+
+pub(crate) fn tag_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'static, str>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    if !is_tag_name(&name) {
+        return Err(de::Error::invalid_value(
+            Unexpected::Str(&name),
+            &"an HTML tag name",
+        ));
+    }
+    Ok(Cow::Owned(name))
+}
+
+pub(crate) fn attribute_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'static, str>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    if !is_attribute_name(&name) {
+        return Err(de::Error::invalid_value(
+            Unexpected::Str(&name),
+            &"an HTML attribute name",
+        ));
+    }
+    Ok(Cow::Owned(name))
+}
+
+/// An ASCII letter followed by letters, digits and the punctuation custom
+/// elements and namespaced tags use.
+fn is_tag_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic())
+        && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Anything but whitespace, controls, quotes, `<`, `>`, `/` and `=`, which
+/// leaves room for names such as `@click` or `hx-on::after-request`.
+fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| {
+            !c.is_whitespace()
+                && !c.is_control()
+                && !matches!(c, '"' | '\'' | '<' | '>' | '/' | '=')
+        })
+}
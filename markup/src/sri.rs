@@ -0,0 +1,255 @@
+use crate::{AttrValue, Attribute, Markup, RegularTag, SelfClosingTag};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fmt;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Hash algorithms accepted in `integrity` attributes, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Algorithm {
+    Sha256,
+    #[default]
+    Sha384,
+    Sha512,
+}
+
+/// An `integrity` attribute that does not match the file it points to.
+#[derive(Debug)]
+pub enum Mismatch {
+    Missing {
+        url: String,
+        path: PathBuf,
+        error: io::Error,
+    },
+    Digest {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+    /// The attribute lists no hash with a supported algorithm.
+    Malformed { url: String, integrity: String },
+    /// The URL path leaves `root`, such as with `..`.
+    OutsideRoot { url: String },
+}
+
+impl Algorithm {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha384 => "sha384",
+            Algorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "sha256" => Some(Algorithm::Sha256),
+            "sha384" => Some(Algorithm::Sha384),
+            "sha512" => Some(Algorithm::Sha512),
+            _ => None,
+        }
+    }
+}
+
+/// Computes an integrity value such as `sha384-oqVuAfXR...`.
+pub fn integrity(bytes: &[u8], algorithm: Algorithm) -> String {
+    let digest = match algorithm {
+        Algorithm::Sha256 => STANDARD.encode(Sha256::digest(bytes)),
+        Algorithm::Sha384 => STANDARD.encode(Sha384::digest(bytes)),
+        Algorithm::Sha512 => STANDARD.encode(Sha512::digest(bytes)),
+    };
+    format!("{}-{digest}", algorithm.prefix())
+}
+
+pub fn integrity_of_file<P: AsRef<Path>>(path: P, algorithm: Algorithm) -> io::Result<String> {
+    Ok(integrity(&std::fs::read(path)?, algorithm))
+}
+
+/// A `<script src integrity crossorigin>` element for the given asset.
+pub fn script<V: Into<AttrValue>>(src: V, bytes: &[u8], algorithm: Algorithm) -> RegularTag {
+    Markup::element("script")
+        .attr("src", src)
+        .attr("integrity", integrity(bytes, algorithm))
+        .attr("crossorigin", "anonymous")
+}
+
+/// A `<link rel="stylesheet" href integrity crossorigin>` element for the
+/// given asset.
+pub fn stylesheet<V: Into<AttrValue>>(
+    href: V,
+    bytes: &[u8],
+    algorithm: Algorithm,
+) -> SelfClosingTag {
+    Markup::self_element("link")
+        .attr("rel", "stylesheet")
+        .attr("href", href)
+        .attr("integrity", integrity(bytes, algorithm))
+        .attr("crossorigin", "anonymous")
+}
+
+pub fn script_from_file<V, P>(src: V, path: P, algorithm: Algorithm) -> io::Result<RegularTag>
+where
+    V: Into<AttrValue>,
+    P: AsRef<Path>,
+{
+    Ok(script(src, &std::fs::read(path)?, algorithm))
+}
+
+pub fn stylesheet_from_file<V, P>(
+    href: V,
+    path: P,
+    algorithm: Algorithm,
+) -> io::Result<SelfClosingTag>
+where
+    V: Into<AttrValue>,
+    P: AsRef<Path>,
+{
+    Ok(stylesheet(href, &std::fs::read(path)?, algorithm))
+}
+
+/// Checks every `integrity` attribute on `<script src>` and `<link href>`
+/// elements against the files under `root` their URLs point to.
+///
+/// Absolute URLs with a scheme or host are skipped, as they are not local
+/// assets. As in browsers, hashes with unknown algorithms are ignored and
+/// only those with the strongest algorithm listed count; the attribute
+/// passes if any of them matches.
+pub fn verify<P: AsRef<Path>>(markup: &Markup, root: P) -> Result<(), Vec<Mismatch>> {
+    let root = root.as_ref();
+    let mut mismatches = Vec::new();
+    let mut processing = vec![markup];
+
+    while let Some(node) = processing.pop() {
+        let (tag, attributes) = match node {
+            Markup::RegularTag(element) => {
+                processing.extend(element.children.iter().rev());
+                (&*element.tag, &element.attributes)
+            }
+            Markup::SelfClosingTag(element) => (&*element.tag, &element.attributes),
+            Markup::Fragment(children) => {
+                processing.extend(children.iter().rev());
+                continue;
+            }
+            Markup::Scoped(scoped) => {
+                processing.push(&scoped.content);
+                continue;
+            }
+            _ => continue,
+        };
+
+        let url_attr = match tag {
+            "script" => "src",
+            "link" => "href",
+            _ => continue,
+        };
+
+        let (Some(url), Some(expected)) = (
+            value_of(attributes, url_attr),
+            value_of(attributes, "integrity"),
+        ) else {
+            continue;
+        };
+
+        if let Err(mismatch) = verify_asset(root, url, expected) {
+            mismatches.push(mismatch);
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+fn verify_asset(root: &Path, url: &str, expected: &str) -> Result<(), Mismatch> {
+    if url.contains("://") || url.starts_with("//") {
+        return Ok(());
+    }
+
+    let relative = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .trim_start_matches('/');
+    if !Path::new(relative)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(Mismatch::OutsideRoot {
+            url: url.to_string(),
+        });
+    }
+    let path = root.join(relative);
+    let bytes = std::fs::read(&path).map_err(|error| Mismatch::Missing {
+        url: url.to_string(),
+        path: path.clone(),
+        error,
+    })?;
+
+    let hashes: Vec<(Algorithm, &str)> = expected
+        .split_whitespace()
+        .filter_map(|hash| {
+            // Options such as `?ct=...` after the digest are not part of the hash.
+            let hash = hash.split('?').next().unwrap_or(hash);
+            let (prefix, _) = hash.split_once('-')?;
+            Some((Algorithm::from_prefix(prefix)?, hash))
+        })
+        .collect();
+    let Some(strongest) = hashes.iter().map(|(algorithm, _)| *algorithm).max() else {
+        return Err(Mismatch::Malformed {
+            url: url.to_string(),
+            integrity: expected.to_string(),
+        });
+    };
+
+    let actual = integrity(&bytes, strongest);
+    if hashes
+        .iter()
+        .any(|(algorithm, hash)| *algorithm == strongest && *hash == actual)
+    {
+        return Ok(());
+    }
+    Err(Mismatch::Digest {
+        url: url.to_string(),
+        expected: expected.to_string(),
+        actual,
+    })
+}
+
+fn value_of<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attr| attr.name.eq_ignore_ascii_case(name))
+        .and_then(|attr| attr.value.as_str())
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Missing { url, path, error } => {
+                write!(f, "{url}: cannot read {}: {error}", path.display())
+            }
+            Mismatch::Digest {
+                url,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "{url}: integrity is {expected} but the file hashes to {actual}"
+                )
+            }
+            Mismatch::Malformed { url, integrity } => {
+                write!(f, "{url}: malformed integrity value `{integrity}`")
+            }
+            Mismatch::OutsideRoot { url } => {
+                write!(f, "{url}: path is outside the asset root")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
@@ -0,0 +1,150 @@
+//! Framing rendered markup as [Server-Sent Events].
+//!
+//! [`encode`] turns anything convertible to an [`Event`] into one framed
+//! event, so a stream or iterator of updates becomes the response body with
+//! a single `map(sse::encode)`.
+//!
+//! [Server-Sent Events]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+
+use crate::{IntoMarkup, Markup, RegularTag, SelfClosingTag};
+use std::fmt;
+use std::time::Duration;
+
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// One event: its payload plus the optional `event`, `id` and `retry` fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    data: String,
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl Event {
+    /// An event carrying `markup` rendered to HTML.
+    pub fn new<M: IntoMarkup>(markup: M) -> Self {
+        Event::data(markup.into_markup().render())
+    }
+
+    /// An event carrying `data` as is. Line breaks of any kind are sent as
+    /// separate `data:` lines, which the browser joins back with `\n`.
+    pub fn data<S: Into<String>>(data: S) -> Self {
+        Event {
+            data: data.into(),
+            ..Event::default()
+        }
+    }
+
+    /// Sets the event name that `addEventListener` listens for. Line breaks
+    /// would end the field early and are dropped.
+    pub fn event<S: Into<String>>(mut self, name: S) -> Self {
+        self.event = Some(field(name.into()));
+        self
+    }
+
+    /// Sets the id the browser sends back in `Last-Event-ID` on reconnect.
+    /// Line breaks and NUL characters are dropped, since a browser ignores
+    /// ids containing them.
+    pub fn id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(field(id.into()).replace('\0', ""));
+        self
+    }
+
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn encode(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Frames `event` for the response body.
+pub fn encode<E: Into<Event>>(event: E) -> String {
+    event.into().encode()
+}
+
+/// A comment line, which clients ignore; useful as a keep-alive.
+pub fn comment(text: &str) -> String {
+    let mut frame = String::new();
+    for line in lines(text) {
+        frame.push(':');
+        if !line.is_empty() {
+            frame.push(' ');
+            frame.push_str(line);
+        }
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
+fn field(value: String) -> String {
+    if value.contains(['\r', '\n']) {
+        value.replace(['\r', '\n'], "")
+    } else {
+        value
+    }
+}
+
+/// Splits on `\r\n`, `\r` and `\n`, the line endings the event stream
+/// parser recognises.
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(text);
+    std::iter::from_fn(move || {
+        let text = rest?;
+        match text.find(['\r', '\n']) {
+            Some(end) => {
+                let skip = if text[end..].starts_with("\r\n") {
+                    2
+                } else {
+                    1
+                };
+                rest = Some(&text[end + skip..]);
+                Some(&text[..end])
+            }
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(event) = &self.event {
+            writeln!(f, "event: {event}")?;
+        }
+        if let Some(id) = &self.id {
+            writeln!(f, "id: {id}")?;
+        }
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+        for line in lines(&self.data) {
+            writeln!(f, "data: {line}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl From<Markup> for Event {
+    fn from(markup: Markup) -> Self {
+        Event::new(markup)
+    }
+}
+
+impl From<RegularTag> for Event {
+    fn from(tag: RegularTag) -> Self {
+        Event::new(tag)
+    }
+}
+
+impl From<SelfClosingTag> for Event {
+    fn from(tag: SelfClosingTag) -> Self {
+        Event::new(tag)
+    }
+}
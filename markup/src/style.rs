@@ -0,0 +1,425 @@
+use crate::AttrValue;
+use std::fmt;
+
+/// A typed list of CSS declarations for an inline `style` attribute.
+///
+/// Typed setters can only produce well-formed values; free-form declarations
+/// go through [`Style::property`], which rejects anything that could escape
+/// the declaration it belongs to.
+#[derive(Debug, Default)]
+pub struct Style {
+    declarations: Vec<(&'static str, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StyleError {
+    InvalidProperty(String),
+    InvalidValue { property: String, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Zero,
+    Auto,
+    Px(f64),
+    Em(f64),
+    Rem(f64),
+    Percent(f64),
+    Vw(f64),
+    Vh(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Rgb(u8, u8, u8),
+    Rgba(u8, u8, u8, f64),
+    Transparent,
+    CurrentColor,
+}
+
+macro_rules! keyword {
+    ($name:ident { $($variant:ident => $css:literal),+ $(,)? }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $css),+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+keyword!(Display {
+    Block => "block",
+    Inline => "inline",
+    InlineBlock => "inline-block",
+    Flex => "flex",
+    InlineFlex => "inline-flex",
+    Grid => "grid",
+    Contents => "contents",
+    None => "none",
+});
+
+keyword!(Position {
+    Static => "static",
+    Relative => "relative",
+    Absolute => "absolute",
+    Fixed => "fixed",
+    Sticky => "sticky",
+});
+
+keyword!(FlexDirection {
+    Row => "row",
+    RowReverse => "row-reverse",
+    Column => "column",
+    ColumnReverse => "column-reverse",
+});
+
+keyword!(Align {
+    Start => "flex-start",
+    End => "flex-end",
+    Center => "center",
+    Stretch => "stretch",
+    Baseline => "baseline",
+    SpaceBetween => "space-between",
+    SpaceAround => "space-around",
+    SpaceEvenly => "space-evenly",
+});
+
+keyword!(TextAlign {
+    Left => "left",
+    Right => "right",
+    Center => "center",
+    Justify => "justify",
+});
+
+keyword!(Overflow {
+    Visible => "visible",
+    Hidden => "hidden",
+    Scroll => "scroll",
+    Auto => "auto",
+});
+
+pub fn px<N: Into<f64>>(value: N) -> Length {
+    Length::Px(value.into())
+}
+
+pub fn em<N: Into<f64>>(value: N) -> Length {
+    Length::Em(value.into())
+}
+
+pub fn rem<N: Into<f64>>(value: N) -> Length {
+    Length::Rem(value.into())
+}
+
+pub fn percent<N: Into<f64>>(value: N) -> Length {
+    Length::Percent(value.into())
+}
+
+pub fn vw<N: Into<f64>>(value: N) -> Length {
+    Length::Vw(value.into())
+}
+
+pub fn vh<N: Into<f64>>(value: N) -> Length {
+    Length::Vh(value.into())
+}
+
+pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb(r, g, b)
+}
+
+pub fn rgba(r: u8, g: u8, b: u8, alpha: f64) -> Color {
+    Color::Rgba(r, g, b, alpha)
+}
+
+/// Builds a color from a `0xRRGGBB` literal.
+pub fn hex(value: u32) -> Color {
+    Color::Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+macro_rules! setters {
+    ($ty:ty => $($method:ident => $property:literal),+ $(,)?) => {
+        $(
+            pub fn $method(self, value: $ty) -> Self {
+                self.set($property, value.to_string())
+            }
+        )+
+    };
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    setters!(Length =>
+        margin => "margin",
+        margin_top => "margin-top",
+        margin_right => "margin-right",
+        margin_bottom => "margin-bottom",
+        margin_left => "margin-left",
+        padding => "padding",
+        padding_top => "padding-top",
+        padding_right => "padding-right",
+        padding_bottom => "padding-bottom",
+        padding_left => "padding-left",
+        width => "width",
+        height => "height",
+        min_width => "min-width",
+        max_width => "max-width",
+        min_height => "min-height",
+        max_height => "max-height",
+        top => "top",
+        right => "right",
+        bottom => "bottom",
+        left => "left",
+        gap => "gap",
+        font_size => "font-size",
+        line_height => "line-height",
+        border_radius => "border-radius",
+    );
+
+    setters!(Color =>
+        color => "color",
+        background_color => "background-color",
+        border_color => "border-color",
+    );
+
+    setters!(Display => display => "display");
+    setters!(Position => position => "position");
+    setters!(FlexDirection => flex_direction => "flex-direction");
+    setters!(Align =>
+        justify_content => "justify-content",
+        align_items => "align-items",
+        align_self => "align-self",
+    );
+    setters!(TextAlign => text_align => "text-align");
+    setters!(Overflow => overflow => "overflow");
+
+    pub fn opacity(self, value: f64) -> Self {
+        self.set("opacity", number(value.clamp(0.0, 1.0)))
+    }
+
+    pub fn z_index(self, value: i32) -> Self {
+        self.set("z-index", value.to_string())
+    }
+
+    pub fn font_weight(self, weight: u16) -> Self {
+        self.set("font-weight", weight.clamp(1, 1000).to_string())
+    }
+
+    /// Sets `font-family`, quoting and escaping every family name except
+    /// generic ones such as `sans-serif`, which only work unquoted.
+    pub fn font_family<I, S>(self, families: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let value = families
+            .into_iter()
+            .map(|family| {
+                let family = family.as_ref();
+                if GENERIC_FAMILIES
+                    .iter()
+                    .any(|generic| generic.eq_ignore_ascii_case(family))
+                {
+                    family.to_string()
+                } else {
+                    css_string(family)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.set("font-family", value)
+    }
+
+    /// Adds a free-form declaration after validating both sides.
+    pub fn property(self, property: &'static str, value: &str) -> Result<Self, StyleError> {
+        if !is_valid_property(property) {
+            return Err(StyleError::InvalidProperty(property.to_string()));
+        }
+        if !is_valid_value(value) {
+            return Err(StyleError::InvalidValue {
+                property: property.to_string(),
+                value: value.to_string(),
+            });
+        }
+        Ok(self.set(property, value.trim().to_string()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    fn set(mut self, property: &'static str, value: String) -> Self {
+        match self.declarations.iter_mut().find(|(p, _)| *p == property) {
+            Some(existing) => existing.1 = value,
+            None => self.declarations.push((property, value)),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (property, value)) in self.declarations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{property}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Style> for AttrValue {
+    fn from(style: Style) -> Self {
+        AttrValue::Owned(style.to_string())
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, unit) = match *self {
+            Length::Zero => return f.write_str("0"),
+            Length::Auto => return f.write_str("auto"),
+            Length::Px(v) => (v, "px"),
+            Length::Em(v) => (v, "em"),
+            Length::Rem(v) => (v, "rem"),
+            Length::Percent(v) => (v, "%"),
+            Length::Vw(v) => (v, "vw"),
+            Length::Vh(v) => (v, "vh"),
+        };
+        write!(f, "{}{unit}", number(value))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Color::Rgb(r, g, b) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+            Color::Rgba(r, g, b, a) => {
+                write!(f, "rgba({r}, {g}, {b}, {})", number(a.clamp(0.0, 1.0)))
+            }
+            Color::Transparent => f.write_str("transparent"),
+            Color::CurrentColor => f.write_str("currentColor"),
+        }
+    }
+}
+
+impl fmt::Display for StyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleError::InvalidProperty(property) => {
+                write!(f, "invalid CSS property name `{property}`")
+            }
+            StyleError::InvalidValue { property, value } => {
+                write!(f, "invalid value `{value}` for CSS property `{property}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StyleError {}
+
+/// Formats a finite number; NaN and infinities collapse to zero.
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "0".to_string()
+    }
+}
+
+const GENERIC_FAMILIES: &[&str] = &[
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "math",
+    "emoji",
+    "fangsong",
+];
+
+/// Quotes a CSS string, escaping quotes, backslashes and control characters.
+fn css_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                output.push('\\');
+                output.push(c);
+            }
+            c if c.is_control() => output.push_str(&format!("\\{:x} ", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+fn is_valid_property(property: &str) -> bool {
+    let name = property.strip_prefix("--").unwrap_or(property);
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Accepts values whose quotes and parentheses are balanced and which cannot
+/// terminate the declaration or the surrounding block.
+fn is_valid_value(value: &str) -> bool {
+    let value = value.trim();
+    if value.is_empty() || value.chars().any(char::is_control) {
+        return false;
+    }
+
+    let lowercase = value.to_ascii_lowercase();
+    if lowercase.contains("expression(") || lowercase.contains("javascript:") {
+        return false;
+    }
+
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+    let mut escaped = false;
+
+    for byte in value.bytes() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match (quote, byte) {
+            (_, b'\\') => escaped = true,
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'(') => depth += 1,
+            (None, b')') if depth == 0 => return false,
+            (None, b')') => depth -= 1,
+            (None, b';' | b'{' | b'}' | b'<' | b'>') => return false,
+            _ => {}
+        }
+    }
+
+    quote.is_none() && depth == 0 && !escaped
+}
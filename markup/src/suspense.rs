@@ -0,0 +1,194 @@
+//! Out-of-order streaming of slow parts of a page.
+
+use crate::chunks::Chunks;
+use crate::future::{Async, BoxFuture};
+use crate::render::{self, RenderError, RenderOptions};
+use crate::{IntoMarkup, Markup};
+use std::fmt;
+use std::future::Future;
+use std::task::{Context, Poll};
+
+/// Swaps a boundary's content in for its fallback: removes the nodes
+/// between the start marker and the end comment, then replaces the marker
+/// with the template's content.
+const SWAP: &str = "(function(i){var p=document.getElementById(i),t=document.getElementById(i+\"-content\"),n=p.nextSibling;while(n&&!(n.nodeType===8&&n.data===\"/\"+i)){var x=n.nextSibling;n.remove();n=x}if(n)n.remove();p.replaceWith(t.content);t.remove()})";
+
+/// A boundary showing `fallback` until its async content is ready.
+///
+/// Over [`Markup::into_stream`] the page is sent with the fallback in place
+/// and the content follows at the end of the response, in a `<template>`
+/// with a small inline script that moves it into place. [`Markup::resolve`]
+/// and [`Markup::render_async`] render the content directly, and the
+/// synchronous renderers only render the fallback.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Suspense {
+    pub fallback: Box<Markup>,
+    pub(crate) content: Async,
+}
+
+/// Renders a tree as a stream of chunks, sending [`Suspense`] content as it
+/// resolves; see [`Markup::into_stream`].
+///
+/// Chunks are pulled with [`Streaming::poll_chunk`] or
+/// [`Streaming::next_chunk`], which work under any executor.
+pub struct Streaming {
+    chunks: Chunks,
+    shell_done: bool,
+    pending: Vec<(usize, BoxFuture)>,
+    /// Rendered boundaries waiting for the end of the page.
+    ready: String,
+}
+
+impl Suspense {
+    pub fn new<M, F, C>(fallback: M, content: F) -> Self
+    where
+        M: IntoMarkup,
+        F: Future<Output = C> + Send + 'static,
+        C: IntoMarkup,
+    {
+        Suspense {
+            fallback: Box::new(fallback.into_markup()),
+            content: Async::new(content),
+        }
+    }
+
+    pub fn into_markup(self) -> Markup {
+        Markup::Suspense(self)
+    }
+}
+
+impl IntoMarkup for Suspense {
+    fn into_markup(self) -> Markup {
+        Markup::Suspense(self)
+    }
+}
+
+impl Markup {
+    /// A [`Suspense`] boundary showing `fallback` until `content` is ready.
+    pub fn suspense<M, F, C>(fallback: M, content: F) -> Markup
+    where
+        M: IntoMarkup,
+        F: Future<Output = C> + Send + 'static,
+        C: IntoMarkup,
+    {
+        Markup::Suspense(Suspense::new(fallback, content))
+    }
+
+    /// Like [`Markup::into_chunks`], but [`Suspense`] content is awaited and
+    /// appended once the rest of the page has been sent.
+    pub fn into_stream(self, chunk_size: usize) -> Streaming {
+        self.into_stream_with(chunk_size, RenderOptions::default())
+    }
+
+    pub fn into_stream_with(self, chunk_size: usize, options: RenderOptions) -> Streaming {
+        let mut chunks = self.into_chunks_with(chunk_size, options);
+        chunks.suspend = true;
+        chunks.into()
+    }
+
+    /// Whether the tree holds a [`Suspense`] boundary outside of cached
+    /// subtrees, so the integrations know to stream it.
+    #[cfg(any(feature = "actix", feature = "axum"))]
+    pub(crate) fn suspends(&self) -> bool {
+        match self {
+            Markup::Suspense(_) => true,
+            Markup::RegularTag(element) => element.children.iter().any(Markup::suspends),
+            Markup::Fragment(children) => children.iter().any(Markup::suspends),
+            Markup::Scoped(scoped) => scoped.content.suspends(),
+            Markup::Provide(provide) => provide.content.suspends(),
+            _ => false,
+        }
+    }
+}
+
+impl Streaming {
+    /// Polls for the next chunk of HTML. The page itself comes first; the
+    /// content of each boundary follows in the order it resolves, with all
+    /// of them loading concurrently from the moment their fallback is sent.
+    pub fn poll_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<String, RenderError>>> {
+        let chunk = if self.shell_done {
+            None
+        } else {
+            self.chunks.next()
+        };
+        let suspended = self.chunks.take_suspended().into_iter();
+        self.pending
+            .extend(suspended.map(|(id, content)| (id, content.future())));
+
+        if let Err(error) = self.poll_pending(cx) {
+            return self.fail(error);
+        }
+        match chunk {
+            Some(Err(error)) => return self.fail(error),
+            Some(Ok(html)) => return Poll::Ready(Some(Ok(html))),
+            None => self.shell_done = true,
+        }
+
+        if !self.ready.is_empty() {
+            Poll::Ready(Some(Ok(std::mem::take(&mut self.ready))))
+        } else if self.pending.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    pub async fn next_chunk(&mut self) -> Option<Result<String, RenderError>> {
+        std::future::poll_fn(|cx| self.poll_chunk(cx)).await
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Result<(), RenderError> {
+        let mut i = 0;
+        while i < self.pending.len() {
+            let Poll::Ready(content) = self.pending[i].1.as_mut().poll(cx) else {
+                i += 1;
+                continue;
+            };
+            let (id, _) = self.pending.remove(i);
+            let content = content.render_with(self.chunks.options())?;
+            let id = self::id(id);
+            self.ready.push_str(&format!(
+                r#"<template id="{id}-content">{content}</template>"#
+            ));
+            self.ready.push_str("<script");
+            render::render_nonce(self.chunks.options(), &mut self.ready);
+            self.ready.push_str(&format!(r#">{SWAP}("{id}")</script>"#));
+        }
+        Ok(())
+    }
+
+    fn fail(&mut self, error: RenderError) -> Poll<Option<Result<String, RenderError>>> {
+        self.shell_done = true;
+        self.pending.clear();
+        self.ready.clear();
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+impl fmt::Debug for Streaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Streaming")
+            .field("chunks", &self.chunks)
+            .field("shell_done", &self.shell_done)
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<Chunks> for Streaming {
+    fn from(chunks: Chunks) -> Self {
+        Streaming {
+            chunks,
+            shell_done: false,
+            pending: Vec::new(),
+            ready: String::new(),
+        }
+    }
+}
+
+pub(crate) fn id(boundary: usize) -> String {
+    format!("suspense-{boundary}")
+}
@@ -0,0 +1,625 @@
+//! Helpers for asserting on rendered HTML.
+//!
+//! Both sides are parsed into a small tree before comparing, so attribute
+//! order, entity spelling and insignificant whitespace do not cause
+//! failures. Text is compared with whitespace runs collapsed and trimmed,
+//! except inside `<pre>`, `<textarea>`, `<script>` and `<style>`.
+//!
+//! [`assert_snapshot!`] stores the same normalized form, pretty-printed, under
+//! `tests/snapshots/` so template changes show up as file diffs.
+
+use crate::Markup;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::Path;
+use std::{env, fs, io};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+    Comment(String),
+    Doctype(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    pub tag: String,
+    /// Sorted by name; attributes without a value have an empty one.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+/// Anything [`assert_html_eq!`](crate::assert_html_eq) can compare.
+pub trait AsHtml {
+    fn as_html(&self) -> Cow<'_, str>;
+}
+
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+const RAW_TEXT: &[&str] = &["script", "style"];
+const PRESERVE_WHITESPACE: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Parses `html` into normalized nodes. Parsing is lenient: unknown end
+/// tags are ignored and elements left open are closed at the end.
+pub fn parse(html: &str) -> Vec<Node> {
+    let mut root = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let node = if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").unwrap_or(after.len());
+            rest = after.get(end + 3..).unwrap_or("");
+            Node::Comment(after[..end].trim().to_string())
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').unwrap_or(after.len());
+            let tag = after[..end].trim().to_ascii_lowercase();
+            rest = after.get(end + 1..).unwrap_or("");
+            if let Some(depth) = stack.iter().rposition(|element| element.tag == tag) {
+                while stack.len() > depth {
+                    close(&mut stack, &mut root);
+                }
+            }
+            continue;
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            let end = after.find('>').unwrap_or(after.len());
+            rest = after.get(end + 1..).unwrap_or("");
+            Node::Doctype(after[..end].trim().to_ascii_lowercase())
+        } else if rest.starts_with('<')
+            && rest.as_bytes().get(1).is_some_and(u8::is_ascii_alphabetic)
+        {
+            let (mut element, self_closing, after) = open_tag(&rest[1..]);
+            rest = after;
+            if RAW_TEXT.contains(&&*element.tag) {
+                let end = rest
+                    .to_ascii_lowercase()
+                    .find(&format!("</{}", element.tag))
+                    .unwrap_or(rest.len());
+                if end > 0 {
+                    element.children.push(Node::Text(rest[..end].to_string()));
+                }
+                rest = &rest[end..];
+                rest = rest.find('>').map_or("", |close| &rest[close + 1..]);
+            } else if !self_closing && !VOID.contains(&&*element.tag) {
+                stack.push(element);
+                continue;
+            }
+            Node::Element(element)
+        } else {
+            // A `<` that opens no tag is text; resume the search after it.
+            let skip = usize::from(rest.starts_with('<'));
+            let end = rest[skip..].find('<').map_or(rest.len(), |end| end + skip);
+            let text = decode(&rest[..end]);
+            rest = &rest[end..];
+            Node::Text(text)
+        };
+        push(&mut stack, &mut root, node);
+    }
+
+    while !stack.is_empty() {
+        close(&mut stack, &mut root);
+    }
+    normalize(root, false)
+}
+
+/// Compares two HTML documents, returning a report of where they differ.
+pub fn html_diff(left: &str, right: &str) -> Option<String> {
+    let left = parse(left);
+    let right = parse(right);
+    if left == right {
+        return None;
+    }
+
+    let mut report = String::new();
+    if let Some((path, left_node, right_node)) = first_difference(&left, &right, &mut Vec::new()) {
+        let path = if path.is_empty() {
+            "root".to_string()
+        } else {
+            path
+        };
+        writeln!(report, "first difference at {path}").unwrap();
+        writeln!(report, "   left: {left_node}").unwrap();
+        writeln!(report, "  right: {right_node}").unwrap();
+    }
+    report.push_str("\n- left\n+ right\n");
+    report.push_str(&line_diff(&pretty(&left), &pretty(&right)));
+    Some(report)
+}
+
+/// Renders nodes as an indented tree, one node per line.
+pub fn pretty(nodes: &[Node]) -> String {
+    let mut output = String::new();
+    write_tree(nodes, 0, &mut output);
+    output
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_html_eq(left: &dyn AsHtml, right: &dyn AsHtml, message: Option<std::fmt::Arguments>) {
+    if let Some(report) = html_diff(&left.as_html(), &right.as_html()) {
+        match message {
+            Some(message) => {
+                panic!("assertion `left == right` failed (as HTML): {message}\n{report}")
+            }
+            None => panic!("assertion `left == right` failed (as HTML)\n{report}"),
+        }
+    }
+}
+
+/// Asserts that two HTML documents are equivalent once parsed, printing a
+/// tree diff pointing at the first differing node when they are not.
+///
+/// Either side may be a [`Markup`] or a string.
+#[macro_export]
+macro_rules! assert_html_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::testing::assert_html_eq(&$left, &$right, None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::testing::assert_html_eq(&$left, &$right, Some(format_args!($($arg)+)))
+    };
+}
+
+/// Environment variable that makes [`assert_snapshot!`] overwrite snapshots
+/// that differ instead of failing.
+pub const UPDATE_SNAPSHOTS: &str = "MARKUP_UPDATE_SNAPSHOTS";
+
+/// Formats `html` with one node per line and sorted attributes; the form
+/// snapshots are stored in.
+pub fn pretty_html(html: &str) -> String {
+    let mut output = String::new();
+    write_html(&parse(html), 0, &mut output);
+    output
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_snapshot(directory: &Path, name: &str, value: &dyn AsHtml) {
+    let update = env::var_os(UPDATE_SNAPSHOTS).is_some_and(|value| value != "0");
+    let ci = env::var_os("CI").is_some();
+    check_snapshot(directory, name, value, update, ci);
+}
+
+/// [`assert_snapshot`] with the environment read by the caller. Under `ci`
+/// a missing snapshot fails instead of being written, unless `update` is
+/// set.
+#[doc(hidden)]
+#[track_caller]
+pub fn check_snapshot(directory: &Path, name: &str, value: &dyn AsHtml, update: bool, ci: bool) {
+    let path = directory.join(format!("{name}.html"));
+    let actual = pretty_html(&value.as_html());
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            if ci && !update {
+                panic!(
+                    "snapshot `{name}` is missing at {}; set {UPDATE_SNAPSHOTS}=1 to write it",
+                    path.display()
+                );
+            }
+            write_snapshot(&path, &actual);
+            return;
+        }
+        Err(error) => panic!("cannot read snapshot {}: {error}", path.display()),
+    };
+    if expected == actual {
+        return;
+    }
+    if update {
+        write_snapshot(&path, &actual);
+        return;
+    }
+
+    panic!(
+        "snapshot `{name}` does not match {}; set {UPDATE_SNAPSHOTS}=1 to accept the new output\n\n- snapshot\n+ actual\n{}",
+        path.display(),
+        line_diff(&expected, &actual)
+    );
+}
+
+#[doc(hidden)]
+pub fn snapshot_name(function: &str) -> String {
+    let mut segments: Vec<&str> = function
+        .split("::")
+        .filter(|segment| *segment != "{{closure}}")
+        .collect();
+    segments.pop();
+    segments.remove(0);
+    segments.join("__")
+}
+
+/// Compares the pretty-printed HTML of a [`Markup`] or string with the
+/// snapshot stored in `tests/snapshots/<name>.html`.
+///
+/// The name defaults to the module path and name of the calling function.
+/// A missing snapshot is written and the assertion passes, except when the
+/// `CI` environment variable is set; a differing one fails with a line diff.
+/// With [`UPDATE_SNAPSHOTS`] set, snapshots are always written.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $value:expr $(,)?) => {
+        $crate::testing::assert_snapshot(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots"),
+            &$name,
+            &$value,
+        )
+    };
+    ($value:expr $(,)?) => {{
+        fn f() {}
+        $crate::assert_snapshot!(
+            $crate::testing::snapshot_name(::std::any::type_name_of_val(&f)),
+            $value
+        )
+    }};
+}
+
+pub use crate::{assert_html_eq, assert_snapshot};
+
+impl AsHtml for str {
+    fn as_html(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl AsHtml for String {
+    fn as_html(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl AsHtml for Markup {
+    fn as_html(&self) -> Cow<'_, str> {
+        Cow::Owned(self.render())
+    }
+}
+
+impl<T: AsHtml + ?Sized> AsHtml for &T {
+    fn as_html(&self) -> Cow<'_, str> {
+        (**self).as_html()
+    }
+}
+
+/// Appends `node` to the open element, merging it into a preceding text node.
+fn push(stack: &mut [Element], root: &mut Vec<Node>, node: Node) {
+    let siblings = match stack.last_mut() {
+        Some(parent) => &mut parent.children,
+        None => root,
+    };
+    match (siblings.last_mut(), node) {
+        (Some(Node::Text(previous)), Node::Text(text)) => previous.push_str(&text),
+        (_, node) => siblings.push(node),
+    }
+}
+
+fn close(stack: &mut Vec<Element>, root: &mut Vec<Node>) {
+    if let Some(element) = stack.pop() {
+        push(stack, root, Node::Element(element));
+    }
+}
+
+/// Parses an opening tag after its `<`, returning the element, whether it
+/// was written as `<tag />`, and the input after the tag.
+fn open_tag(input: &str) -> (Element, bool, &str) {
+    let name_end = input
+        .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .unwrap_or(input.len());
+    let mut element = Element {
+        tag: input[..name_end].to_ascii_lowercase(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut rest = &input[name_end..];
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return (element, true, after);
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return (element, false, after);
+        }
+        if rest.is_empty() {
+            return (element, false, rest);
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        if name_end == 0 {
+            rest = &rest[1..];
+            continue;
+        }
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let end = body.find(quote).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    after.split_at(end)
+                }
+            };
+            value = decode(raw);
+            rest = remaining;
+        }
+        element.attributes.push((name, value));
+    }
+}
+
+/// Decodes character references, leaving unknown ones as written.
+fn decode(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let character = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => '\u{a0}',
+                name => {
+                    let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => name.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((character, end))
+        });
+
+        match decoded {
+            Some((character, end)) => {
+                output.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn normalize(nodes: Vec<Node>, preserve: bool) -> Vec<Node> {
+    let mut output = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        output.push(match node {
+            Node::Text(text) if preserve => Node::Text(text),
+            Node::Text(text) => {
+                let text = text.split_ascii_whitespace().collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    continue;
+                }
+                Node::Text(text)
+            }
+            Node::Element(mut element) => {
+                let preserve = preserve || PRESERVE_WHITESPACE.contains(&&*element.tag);
+                element.children = normalize(element.children, preserve);
+                element.attributes.sort_by(|a, b| a.0.cmp(&b.0));
+                Node::Element(element)
+            }
+            other => other,
+        });
+    }
+
+    output
+}
+
+/// Finds the first node that differs, as its path and both sides' summary.
+fn first_difference(
+    left: &[Node],
+    right: &[Node],
+    path: &mut Vec<String>,
+) -> Option<(String, String, String)> {
+    for i in 0..left.len().max(right.len()) {
+        let (left, right) = (left.get(i), right.get(i));
+        if left == right {
+            continue;
+        }
+
+        let name = match left.or(right) {
+            Some(Node::Element(element)) => format!("{}[{i}]", element.tag),
+            Some(Node::Text(_)) => format!("#text[{i}]"),
+            Some(Node::Comment(_)) => format!("#comment[{i}]"),
+            _ => format!("[{i}]"),
+        };
+        path.push(name);
+
+        if let (Some(Node::Element(l)), Some(Node::Element(r))) = (left, right)
+            && l.tag == r.tag
+            && l.attributes == r.attributes
+            && let Some(found) = first_difference(&l.children, &r.children, path)
+        {
+            return Some(found);
+        }
+
+        let found = (path.join(" > "), summary(left), summary(right));
+        return Some(found);
+    }
+    None
+}
+
+fn summary(node: Option<&Node>) -> String {
+    match node {
+        Some(Node::Element(element)) => open(element),
+        Some(Node::Text(text)) => format!("{text:?}"),
+        Some(Node::Comment(comment)) => format!("<!-- {comment} -->"),
+        Some(Node::Doctype(doctype)) => format!("<!{doctype}>"),
+        None => "(nothing)".to_string(),
+    }
+}
+
+fn open(element: &Element) -> String {
+    let mut tag = format!("<{}", element.tag);
+    for (name, value) in &element.attributes {
+        if value.is_empty() {
+            write!(tag, " {name}").unwrap();
+        } else {
+            write!(tag, " {name}={value:?}").unwrap();
+        }
+    }
+    tag.push('>');
+    tag
+}
+
+fn write_tree(nodes: &[Node], depth: usize, output: &mut String) {
+    for node in nodes {
+        output.push_str(&"  ".repeat(depth));
+        output.push_str(&summary(Some(node)));
+        output.push('\n');
+        if let Node::Element(element) = node {
+            write_tree(&element.children, depth + 1, output);
+        }
+    }
+}
+
+/// A line-by-line diff, marking lines only on the left with `-` and lines
+/// only on the right with `+`.
+fn line_diff(left: &str, right: &str) -> String {
+    let left: Vec<&str> = left.lines().collect();
+    let right: Vec<&str> = right.lines().collect();
+
+    let mut common = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i][j] = if left[i] == right[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            writeln!(output, "  {}", left[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && common[i + 1][j] >= common[i][j + 1]) {
+            writeln!(output, "- {}", left[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(output, "+ {}", right[j]).unwrap();
+            j += 1;
+        }
+    }
+    output
+}
+
+fn write_snapshot(path: &Path, content: &str) {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)
+            .unwrap_or_else(|error| panic!("cannot create {}: {error}", directory.display()));
+    }
+    fs::write(path, content)
+        .unwrap_or_else(|error| panic!("cannot write snapshot {}: {error}", path.display()));
+}
+
+fn write_html(nodes: &[Node], depth: usize, output: &mut String) {
+    for node in nodes {
+        output.push_str(&"  ".repeat(depth));
+        match node {
+            Node::Element(element) => {
+                write_open(element, output);
+                if VOID.contains(&&*element.tag) && element.children.is_empty() {
+                    output.push('\n');
+                    continue;
+                }
+
+                let inline = PRESERVE_WHITESPACE.contains(&&*element.tag)
+                    || match &*element.children {
+                        [] => true,
+                        [Node::Text(text)] => text.len() <= 60,
+                        _ => false,
+                    };
+                if inline {
+                    write_inline(element, &element.children, output);
+                } else {
+                    output.push('\n');
+                    write_html(&element.children, depth + 1, output);
+                    output.push_str(&"  ".repeat(depth));
+                }
+                writeln!(output, "</{}>", element.tag).unwrap();
+            }
+            Node::Text(text) => {
+                write_text(text, output);
+                output.push('\n');
+            }
+            other => writeln!(output, "{}", summary(Some(other))).unwrap(),
+        }
+    }
+}
+
+/// Writes children on the current line, as written; used where whitespace
+/// is significant.
+fn write_inline(parent: &Element, nodes: &[Node], output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) if RAW_TEXT.contains(&&*parent.tag) => output.push_str(text),
+            Node::Text(text) => write_text(text, output),
+            Node::Element(element) => {
+                write_open(element, output);
+                if !VOID.contains(&&*element.tag) {
+                    write_inline(element, &element.children, output);
+                    write!(output, "</{}>", element.tag).unwrap();
+                }
+            }
+            other => output.push_str(&summary(Some(other))),
+        }
+    }
+}
+
+fn write_open(element: &Element, output: &mut String) {
+    write!(output, "<{}", element.tag).unwrap();
+    for (name, value) in &element.attributes {
+        write!(output, " {name}").unwrap();
+        if !value.is_empty() {
+            output.push_str("=\"");
+            for c in value.chars() {
+                match c {
+                    '&' => output.push_str("&amp;"),
+                    '"' => output.push_str("&quot;"),
+                    c => output.push(c),
+                }
+            }
+            output.push('"');
+        }
+    }
+    output.push('>');
+}
+
+fn write_text(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! [Turbo Stream] elements wrapping rendered markup.
+//!
+//! Each helper returns a `<turbo-stream>` element, which can be rendered
+//! into a response with [`CONTENT_TYPE`] or pushed over a socket or
+//! [`sse`](crate::sse) event.
+//!
+//! [Turbo Stream]: https://turbo.hotwired.dev/handbook/streams
+
+use crate::{AttrValue, IntoMarkup, Markup};
+
+pub const CONTENT_TYPE: &str = "text/vnd.turbo-stream.html";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Append,
+    Prepend,
+    Replace,
+    Update,
+    Remove,
+    Before,
+    After,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Append => "append",
+            Action::Prepend => "prepend",
+            Action::Replace => "replace",
+            Action::Update => "update",
+            Action::Remove => "remove",
+            Action::Before => "before",
+            Action::After => "after",
+        }
+    }
+}
+
+/// Applies `action` with `content` to the element whose id is `target`.
+pub fn stream<V, C>(action: Action, target: V, content: C) -> Markup
+where
+    V: Into<AttrValue>,
+    C: IntoMarkup,
+{
+    element(action, "target", target.into(), Some(content.into_markup()))
+}
+
+/// Applies `action` with `content` to every element matching the CSS
+/// selector `targets`.
+pub fn stream_all<V, C>(action: Action, targets: V, content: C) -> Markup
+where
+    V: Into<AttrValue>,
+    C: IntoMarkup,
+{
+    element(
+        action,
+        "targets",
+        targets.into(),
+        Some(content.into_markup()),
+    )
+}
+
+pub fn append<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::Append, target, content)
+}
+
+pub fn prepend<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::Prepend, target, content)
+}
+
+pub fn replace<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::Replace, target, content)
+}
+
+pub fn update<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::Update, target, content)
+}
+
+pub fn before<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::Before, target, content)
+}
+
+pub fn after<V: Into<AttrValue>, C: IntoMarkup>(target: V, content: C) -> Markup {
+    stream(Action::After, target, content)
+}
+
+/// Removes the element whose id is `target`; no template is sent.
+pub fn remove<V: Into<AttrValue>>(target: V) -> Markup {
+    element(Action::Remove, "target", target.into(), None)
+}
+
+fn element(
+    action: Action,
+    name: &'static str,
+    target: AttrValue,
+    content: Option<Markup>,
+) -> Markup {
+    let mut element = Markup::element("turbo-stream")
+        .attr("action", action.as_str())
+        .attr(name, target);
+    if let Some(content) = content {
+        element = element.child(Markup::element("template").child(content));
+    }
+    element.into_markup()
+}
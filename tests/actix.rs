@@ -0,0 +1,116 @@
+#![cfg(feature = "actix")]
+
+#[cfg(test)]
+mod actix_tests {
+    use actix_web::body::{BodySize, MessageBody};
+    use actix_web::http::StatusCode;
+    use actix_web::http::header::{self, HeaderValue};
+    use actix_web::{App, test, web};
+    use markup::actix::Html;
+    use markup::*;
+
+    fn big_list() -> Markup {
+        Markup::element("ul")
+            .child(
+                (0..5_000)
+                    .map(|i| Markup::element("li").child(format!("Item {i}")))
+                    .into_markup(),
+            )
+            .into_markup()
+    }
+
+    macro_rules! app {
+        () => {
+            test::init_service(
+                App::new()
+                    .route(
+                        "/",
+                        web::get().to(|| async { Markup::element("h1").child("Hello & welcome") }),
+                    )
+                    .route(
+                        "/markup",
+                        web::get()
+                            .to(|| async { Markup::element("p").child("Markup").into_markup() }),
+                    )
+                    .route(
+                        "/void",
+                        web::get().to(|| async { Markup::self_element("hr").class("rule") }),
+                    )
+                    .route("/big", web::get().to(|| async { big_list() }))
+                    .route(
+                        "/missing",
+                        web::get().to(|| async {
+                            Html::new(Markup::element("h1").child("Not found"))
+                                .status(StatusCode::NOT_FOUND)
+                                .header((header::CACHE_CONTROL, "no-store"))
+                        }),
+                    )
+                    .route(
+                        "/streamed",
+                        web::get().to(|| async {
+                            Html::new(Markup::element("p").child("small")).streaming(true)
+                        }),
+                    ),
+            )
+            .await
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_tags_and_markup_respond_with_html() {
+        let app = app!();
+
+        for (path, expected) in [
+            ("/", "<h1>Hello &amp; welcome</h1>"),
+            ("/markup", "<p>Markup</p>"),
+            ("/void", "<hr class=\"rule\" />"),
+        ] {
+            let response =
+                test::call_service(&app, test::TestRequest::get().uri(path).to_request()).await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE),
+                Some(&HeaderValue::from_static("text/html; charset=utf-8"))
+            );
+            assert_eq!(test::read_body(response).await, expected);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_wrapper_sets_status_and_headers() {
+        let app = app!();
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/missing").to_request()).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL),
+            Some(&HeaderValue::from_static("no-store"))
+        );
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE),
+            Some(&HeaderValue::from_static("text/html; charset=utf-8"))
+        );
+        assert_eq!(test::read_body(response).await, "<h1>Not found</h1>");
+    }
+
+    #[actix_web::test]
+    async fn test_large_and_forced_pages_are_streamed() {
+        let app = app!();
+
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/big").to_request()).await;
+        assert_eq!(response.response().body().size(), BodySize::Stream);
+        assert_eq!(test::read_body(response).await, big_list().render());
+
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/streamed").to_request()).await;
+        assert_eq!(response.response().body().size(), BodySize::Stream);
+        assert_eq!(test::read_body(response).await, "<p>small</p>");
+
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/markup").to_request()).await;
+        assert_eq!(response.response().body().size(), BodySize::Sized(13));
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "arena")]
+
+#[cfg(test)]
+mod arena_tests {
+    use markup::arena::{Arena, IntoArena};
+    use markup::{IntoMarkup, Markup, RenderOptions};
+
+    #[test]
+    fn test_arena_matches_vec_tree() {
+        let arena = Arena::new();
+        let items = ["One", "Two & Three"];
+
+        let arena_markup = arena
+            .element("ul")
+            .class("list")
+            .class("list")
+            .attr("data-count", items.len().to_string())
+            .child(
+                items
+                    .iter()
+                    .map(|item| arena.element("li").child(*item))
+                    .into_arena(&arena),
+            )
+            .child(arena.self_element("hr").attr("hidden", true))
+            .child(None::<&str>)
+            .into_markup();
+
+        let vec_markup = Markup::element("ul")
+            .class("list")
+            .class("list")
+            .attr("data-count", items.len().to_string())
+            .child(
+                items
+                    .iter()
+                    .map(|item| Markup::element("li").child(*item))
+                    .into_markup(),
+            )
+            .child(Markup::self_element("hr").attr("hidden", true))
+            .child(None::<&str>)
+            .into_markup();
+
+        assert_eq!(arena_markup.render(), vec_markup.render());
+        assert_eq!(arena_markup.size_hint(), vec_markup.size_hint());
+    }
+
+    #[test]
+    fn test_arena_render_options() {
+        let arena = Arena::new();
+        let markup = arena
+            .element("script")
+            .child(arena.raw("run()"))
+            .into_markup();
+        assert_eq!(
+            markup
+                .render_with(&RenderOptions::new().nonce("n0").unwrap())
+                .unwrap(),
+            r#"<script nonce="n0">run()</script>"#
+        );
+    }
+
+    #[test]
+    fn test_arena_reset_reuses_memory() {
+        let mut arena = Arena::new();
+        let mut rendered = Vec::new();
+
+        for request in 0..3 {
+            arena.reset();
+            let markup = arena
+                .element("p")
+                .child(arena.text(&format!("request {request}")))
+                .into_markup();
+            rendered.push(markup.render());
+        }
+
+        assert_eq!(
+            rendered,
+            ["<p>request 0</p>", "<p>request 1</p>", "<p>request 2</p>"]
+        );
+    }
+}
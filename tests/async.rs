@@ -0,0 +1,145 @@
+mod common;
+
+#[cfg(test)]
+mod async_tests {
+    use crate::common::{Noop, yield_times};
+    use markup::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    /// Polls `future` in a loop, failing if it is not done within `budget`
+    /// polls.
+    fn block_on<F: Future>(future: F, budget: usize) -> F::Output {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        for _ in 0..budget {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+        panic!("future not ready after {budget} polls");
+    }
+
+    fn item(name: &'static str, polls: usize, finished: Arc<Mutex<Vec<&'static str>>>) -> Markup {
+        Markup::from_future(async move {
+            yield_times(polls).await;
+            finished.lock().unwrap().push(name);
+            Markup::element("li").child(name)
+        })
+    }
+
+    #[test]
+    fn test_render_async_preserves_order() {
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let markup = Markup::element("ul")
+            .child(item("slow", 5, finished.clone()))
+            .child(item("fast", 0, finished.clone()))
+            .child(item("medium", 2, finished.clone()))
+            .into_markup();
+
+        assert_eq!(
+            block_on(markup.render_async(), 10),
+            "<ul><li>slow</li><li>fast</li><li>medium</li></ul>"
+        );
+        assert_eq!(*finished.lock().unwrap(), ["fast", "medium", "slow"]);
+    }
+
+    #[test]
+    fn test_siblings_load_concurrently() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let waiting = |name: &'static str| {
+            let started = started.clone();
+            Markup::from_future(async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                std::future::poll_fn(|cx| {
+                    if started.load(Ordering::SeqCst) == 2 {
+                        Poll::Ready(name)
+                    } else {
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                })
+                .await
+            })
+        };
+        let markup = Markup::Fragment(vec![waiting("a"), waiting("b")]);
+        assert_eq!(block_on(markup.render_async(), 10), "ab");
+    }
+
+    #[test]
+    fn test_nested_components() {
+        let card = |id: u32| {
+            Markup::from_future(async move {
+                yield_times(1).await;
+                Markup::element("div")
+                    .child(format!("card {id}"))
+                    .child(Markup::from_future(async move {
+                        yield_times(2).await;
+                        Markup::element("span").child(format!("{}", id * 10))
+                    }))
+            })
+        };
+        let markup = Markup::element("main")
+            .child(card(1))
+            .child(Markup::element("section").child(card(2)))
+            .into_markup();
+
+        let resolved = block_on(markup.resolve(), 10);
+        assert_eq!(
+            resolved.render(),
+            "<main><div>card 1<span>10</span></div><section><div>card 2<span>20</span></div></section></main>"
+        );
+    }
+
+    #[test]
+    fn test_render_async_with_options() {
+        let markup =
+            Markup::from_future(async { Markup::element("button").attr("onclick", "go()") });
+        let options = RenderOptions::new().nonce("abc").unwrap();
+        assert!(block_on(markup.render_async_with(&options), 10).is_err());
+    }
+
+    #[test]
+    fn test_clones_share_the_output() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let page = Markup::element("div")
+            .child(Markup::from_future(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                yield_times(2).await;
+                "loaded"
+            }))
+            .into_markup();
+        let copy = page.clone();
+        assert_eq!(copy, page);
+
+        assert_eq!(block_on(page.render_async(), 10), "<div>loaded</div>");
+        assert_eq!(block_on(copy.render_async(), 10), "<div>loaded</div>");
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "async component rendered before being resolved")]
+    fn test_sync_render_of_unresolved_component() {
+        Markup::from_future(async { "late" }).render();
+    }
+
+    #[test]
+    fn test_unresolved_component_is_an_error() {
+        let markup = Markup::element("p")
+            .child(Markup::from_future(async { "late" }))
+            .into_markup();
+
+        assert_eq!(markup.size_hint(), "<p></p>".len());
+        assert_eq!(
+            markup.render_with(&RenderOptions::new()),
+            Err(RenderError::Unresolved)
+        );
+        let chunks: Vec<_> = markup.into_chunks(8).collect();
+        assert_eq!(chunks.last(), Some(&Err(RenderError::Unresolved)));
+    }
+}
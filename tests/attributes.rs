@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod attribute_tests {
+    use markup::*;
+
+    #[test]
+    fn test_class_merging() {
+        let markup = Markup::element("div")
+            .attr("class", "a b")
+            .attr("class", "b c")
+            .into_markup();
+        assert_eq!(markup.render(), r#"<div class="a b c"></div>"#);
+    }
+
+    #[test]
+    fn test_class_helpers() {
+        let markup = Markup::element("button")
+            .id("submit")
+            .class("btn")
+            .classes(["btn-primary", "btn"])
+            .class_if(true, "active")
+            .class_if(false, "disabled")
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<button id="submit" class="btn btn-primary active"></button>"#
+        );
+    }
+
+    #[test]
+    fn test_style_merging() {
+        let markup = Markup::element("p")
+            .attr("style", "color: red; margin: 0")
+            .attr("style", "color: blue; background: url('a;b.png')")
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<p style="color: blue; margin: 0; background: url(&apos;a;b.png&apos;)"></p>"#
+        );
+    }
+
+    #[test]
+    fn test_duplicate_attribute_replaced() {
+        let markup = Markup::self_element("input")
+            .attr("type", "text")
+            .attr("disabled", true)
+            .attr("type", "email")
+            .attr("disabled", false)
+            .into_markup();
+        assert_eq!(markup.render(), r#"<input type="email" />"#);
+    }
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "axum")]
+
+#[cfg(test)]
+mod axum_tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode, header};
+    use axum::routing::get;
+    use http_body_util::BodyExt;
+    use markup::*;
+    use tower::ServiceExt;
+
+    fn big_list() -> Markup {
+        Markup::element("ul")
+            .child(
+                (0..5_000)
+                    .map(|i| Markup::element("li").child(format!("Item {i}")))
+                    .into_markup(),
+            )
+            .into_markup()
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|| async { Markup::element("h1").child("Hello & welcome") }),
+            )
+            .route(
+                "/markup",
+                get(|| async { Markup::element("p").child("Markup").into_markup() }),
+            )
+            .route(
+                "/void",
+                get(|| async { Markup::self_element("hr").class("rule") }),
+            )
+            .route("/big", get(|| async { big_list() }))
+            .route(
+                "/unresolved",
+                get(|| async { Markup::from_future(async { "late" }) }),
+            )
+    }
+
+    async fn fetch(path: &str) -> (StatusCode, Option<String>, Option<String>, String) {
+        let response = app()
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .map(|value: &header::HeaderValue| value.to_str().unwrap().to_string())
+        };
+        let content_type = header(header::CONTENT_TYPE);
+        let content_length = header(header::CONTENT_LENGTH);
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (
+            status,
+            content_type,
+            content_length,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_tags_and_markup_respond_with_html() {
+        for (path, expected) in [
+            ("/", "<h1>Hello &amp; welcome</h1>"),
+            ("/markup", "<p>Markup</p>"),
+            ("/void", "<hr class=\"rule\" />"),
+        ] {
+            let (status, content_type, content_length, body) = fetch(path).await;
+
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+            assert_eq!(content_length, Some(expected.len().to_string()));
+            assert_eq!(body, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_pages_are_streamed() {
+        let (status, content_type, content_length, body) = fetch("/big").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+        assert_eq!(content_length, None);
+        assert_eq!(body, big_list().render());
+    }
+
+    #[tokio::test]
+    async fn test_render_errors_respond_with_500() {
+        let (status, _, _, body) = fetch("/unresolved").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, RenderError::Unresolved.to_string());
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "http-body")]
+
+#[cfg(test)]
+mod body_tests {
+    use http_body::Body;
+    use http_body_util::BodyExt;
+    use markup::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn rows(count: usize) -> Markup {
+        Markup::element("table")
+            .child(
+                (0..count)
+                    .map(|i| {
+                        Markup::element("tr").child(Markup::element("td").child(format!("{i}")))
+                    })
+                    .into_markup(),
+            )
+            .into_markup()
+    }
+
+    #[tokio::test]
+    async fn test_body_yields_rendered_frames() {
+        let page = rows(3_000);
+        let mut body = MarkupBody::from(page.clone().into_chunks(4096));
+        let mut html = String::new();
+        let mut frames = 0;
+
+        while let Some(frame) = body.frame().await {
+            let data = frame.unwrap().into_data().unwrap();
+            assert!(data.len() < 4096 + 64);
+            html.push_str(std::str::from_utf8(&data).unwrap());
+            frames += 1;
+        }
+
+        assert!(frames > 1);
+        assert!(body.is_end_stream());
+        assert_eq!(html, page.render());
+    }
+
+    #[tokio::test]
+    async fn test_body_renders_lazily() {
+        let builds = Arc::new(AtomicUsize::new(0));
+        let counter = builds.clone();
+        let page = Markup::Fragment(vec![
+            rows(2_000),
+            Markup::cached("body-lazy-footer", move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Markup::element("footer").child("end")
+            })
+            .into_markup(),
+        ]);
+        let mut body = MarkupBody::new(page);
+
+        assert!(!body.is_end_stream());
+        body.frame().await.unwrap().unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+
+        let rest = body.collect().await.unwrap().to_bytes();
+        assert!(rest.ends_with(b"<footer>end</footer>"));
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_body_reports_render_errors() {
+        let page = Markup::element("div")
+            .child(Markup::element("a").attr("onclick", "go()"))
+            .into_markup();
+        let mut body = MarkupBody::from(
+            page.into_chunks_with(1024, RenderOptions::new().nonce("abc").unwrap()),
+        );
+
+        assert!(matches!(
+            body.frame().await,
+            Some(Err(RenderError::InlineEventHandler { .. }))
+        ));
+        assert!(body.frame().await.is_none());
+        assert!(body.is_end_stream());
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod cache_tests {
+    use markup::cache::{FragmentCache, LruCache};
+    use markup::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn header(builds: &Arc<AtomicUsize>) -> Cached {
+        let builds = builds.clone();
+        Markup::cached("header", move || {
+            builds.fetch_add(1, Ordering::SeqCst);
+            Markup::element("header").child("Site & Co")
+        })
+    }
+
+    #[test]
+    fn test_cached_subtree_built_once() {
+        let cache = Arc::new(LruCache::new(8));
+        let options = RenderOptions::new().cache(cache.clone());
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        for page in ["a", "b"] {
+            let markup = Markup::element("body")
+                .child(header(&builds))
+                .child(Markup::element("main").child(page))
+                .into_markup();
+            assert_eq!(
+                markup.render_with(&options).unwrap(),
+                format!("<body><header>Site &amp; Co</header><main>{page}</main></body>")
+            );
+        }
+
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            cache.get("header").as_deref(),
+            Some("<header>Site &amp; Co</header>")
+        );
+    }
+
+    #[test]
+    fn test_invalidation_and_ttl() {
+        let cache = Arc::new(LruCache::new(8));
+        let options = RenderOptions::new().cache(cache.clone());
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        header(&builds).into_markup().render_with(&options).unwrap();
+        cache.invalidate("header");
+        header(&builds).into_markup().render_with(&options).unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+
+        let expiring = header(&builds).ttl(Duration::ZERO).into_markup();
+        cache.clear();
+        expiring.render_with(&options).unwrap();
+        expiring.render_with(&options).unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = LruCache::new(2);
+        cache.insert("a", "A".into(), None);
+        cache.insert("b", "B".into(), None);
+        assert!(cache.get("a").is_some());
+        cache.insert("c", "C".into(), None);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn test_lru_reinsert_and_invalidate() {
+        let cache = LruCache::new(2);
+        cache.insert("a", "A".into(), None);
+        cache.insert("b", "B".into(), None);
+        cache.insert("a", "A2".into(), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b").as_deref(), Some("B"));
+
+        cache.invalidate("b");
+        cache.insert("c", "C".into(), None);
+        assert_eq!(cache.len(), 2);
+        cache.insert("d", "D".into(), None);
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("c").as_deref(), Some("C"));
+        assert_eq!(cache.get("d").as_deref(), Some("D"));
+    }
+
+    #[test]
+    fn test_cache_bypassed_under_nonce() {
+        let cache = Arc::new(LruCache::new(8));
+        let markup = Markup::cached("script", || {
+            Markup::element("script").child(Markup::raw("boot()"))
+        })
+        .into_markup();
+
+        let first = RenderOptions::new()
+            .cache(cache.clone())
+            .nonce("one")
+            .unwrap();
+        let second = RenderOptions::new()
+            .cache(cache.clone())
+            .nonce("two")
+            .unwrap();
+        assert_eq!(
+            markup.render_with(&first).unwrap(),
+            r#"<script nonce="one">boot()</script>"#
+        );
+        assert_eq!(
+            markup.render_with(&second).unwrap(),
+            r#"<script nonce="two">boot()</script>"#
+        );
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_bypassed_for_context() {
+        struct User(&'static str);
+
+        let cache = Arc::new(LruCache::new(8));
+        let markup = Markup::provide(
+            User("Ada"),
+            Markup::cached("greeting", || {
+                Markup::component(|context: &RenderContext| {
+                    let name = context.get::<User>().map_or("guest", |user| user.0);
+                    Markup::element("p").child(format!("Hello, {name}"))
+                })
+            }),
+        );
+        let options = RenderOptions::new().cache(cache.clone());
+
+        assert_eq!(markup.render_with(&options).unwrap(), "<p>Hello, Ada</p>");
+        let chunks: String = markup
+            .into_chunks_with(8, options.clone())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(chunks, "<p>Hello, Ada</p>");
+        assert!(cache.is_empty());
+
+        let markup = Markup::cached("greeting", || Markup::element("p").child("Hello"));
+        let context = RenderContext::new().with(User("Ada"));
+        let html = Markup::provide_context(context, markup.into_markup())
+            .render_with(&options)
+            .unwrap();
+        assert_eq!(html, "<p>Hello</p>");
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod chunks_tests {
+    use markup::*;
+
+    static CARD: ScopedStyle = ScopedStyle::new("card", "p { color: red; }");
+
+    fn rows(count: usize) -> Markup {
+        Markup::element("table")
+            .child(
+                (0..count)
+                    .map(|i| {
+                        Markup::element("tr")
+                            .child(Markup::element("td").child(format!("{i} & <{i}>")))
+                            .child(Markup::self_element("input").attr("value", i.to_string()))
+                    })
+                    .into_markup(),
+            )
+            .into_markup()
+    }
+
+    fn collect(chunks: Chunks) -> String {
+        chunks.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_chunks_match_render() {
+        let table = rows(500);
+        let expected = table.render();
+
+        for size in [1, 7, 64, 4096, 1 << 20] {
+            assert_eq!(collect(table.clone().into_chunks(size)), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunks_are_bounded() {
+        let chunks: Vec<String> = rows(2_000).into_chunks(1024).map(Result::unwrap).collect();
+
+        assert!(chunks.len() > 10);
+        assert!(chunks.iter().all(|chunk| chunk.len() < 1024 + 128));
+    }
+
+    #[test]
+    fn test_scoped_styles_are_placed_like_render() {
+        let scoped = || CARD.scope(Markup::element("div").child(Markup::element("p").child("x")));
+        let pages = [
+            Markup::element("html")
+                .child(Markup::element("head").child(Markup::element("title").child("t")))
+                .child(Markup::element("body").child(scoped()))
+                .into_markup(),
+            Markup::element("html")
+                .child(Markup::element("head").child(Markup::ScopedStyles))
+                .child(Markup::element("body").child(scoped()))
+                .into_markup(),
+            Markup::Fragment(vec![
+                Markup::element("p").child("before").into_markup(),
+                scoped(),
+            ]),
+        ];
+
+        for page in pages {
+            assert_eq!(collect(page.clone().into_chunks(8)), page.render());
+        }
+    }
+
+    #[test]
+    fn test_chunks_with_options_and_errors() {
+        let options = RenderOptions::new().nonce("abc123").unwrap();
+        let page = Markup::element("div")
+            .child(Markup::element("script").child("run()"))
+            .into_markup();
+        assert_eq!(
+            collect(page.clone().into_chunks_with(4, options.clone())),
+            page.render_with(&options).unwrap()
+        );
+
+        let page = Markup::element("div")
+            .child(rows(10))
+            .child(Markup::element("button").attr("onclick", "go()"))
+            .into_markup();
+        let mut chunks = page.into_chunks_with(16, options);
+        assert!(chunks.by_ref().take_while(Result::is_ok).count() > 0);
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    fn test_empty_tree_has_no_chunks() {
+        assert_eq!(Markup::None.into_chunks(16).count(), 0);
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+use std::task::{Poll, Wake};
+
+pub struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Stays pending for `polls` polls, waking itself each time.
+pub async fn yield_times(polls: usize) {
+    let mut left = polls;
+    std::future::poll_fn(|cx| {
+        if left == 0 {
+            return Poll::Ready(());
+        }
+        left -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
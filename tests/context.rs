@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod context_tests {
+    use markup::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Locale {
+        En,
+        Fr,
+    }
+
+    struct User(&'static str);
+
+    fn greeting() -> Markup {
+        Markup::component(|context: &RenderContext| {
+            let hello = match context.get::<Locale>() {
+                Some(Locale::Fr) => "Bonjour",
+                Some(Locale::En) | None => "Hello",
+            };
+            let name = context.get::<User>().map_or("guest", |user| user.0);
+            Markup::element("p").child(format!("{hello}, {name}"))
+        })
+    }
+
+    fn page() -> Markup {
+        Markup::element("main")
+            .child(greeting())
+            .child(Markup::provide(
+                Locale::Fr,
+                Markup::element("section").id("fr").child(greeting()),
+            ))
+            .child(greeting())
+            .into_markup()
+    }
+
+    fn options() -> RenderOptions {
+        RenderOptions::new().context(RenderContext::new().with(Locale::En).with(User("Ada")))
+    }
+
+    #[test]
+    fn test_components_read_context() {
+        assert_eq!(
+            page().render_with(&options()).unwrap(),
+            r#"<main><p>Hello, Ada</p><section id="fr"><p>Bonjour, Ada</p></section><p>Hello, Ada</p></main>"#
+        );
+        assert_eq!(
+            page().render(),
+            r#"<main><p>Hello, guest</p><section id="fr"><p>Bonjour, guest</p></section><p>Hello, guest</p></main>"#
+        );
+    }
+
+    #[test]
+    fn test_context_values() {
+        let mut context = RenderContext::new().with(Locale::En);
+        let copy = context.clone();
+        context.insert(Locale::Fr);
+        context.insert(User("Ada"));
+
+        assert_eq!(context.get::<Locale>(), Some(&Locale::Fr));
+        assert_eq!(copy.get::<Locale>(), Some(&Locale::En));
+        assert!(!copy.contains::<User>());
+        assert_eq!(context.len(), 2);
+    }
+
+    #[test]
+    fn test_chunks_and_fragments_see_context() {
+        let html: String = page()
+            .into_chunks_with(8, options())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(html, page().render_with(&options()).unwrap());
+
+        assert_eq!(
+            page()
+                .render_fragment_with("fr", &options())
+                .unwrap()
+                .unwrap(),
+            r#"<section id="fr"><p>Bonjour, Ada</p></section>"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_provide_across_parallel_render() {
+        let markup = Markup::provide(
+            Locale::Fr,
+            Markup::element("div").child((0..64).map(|_| greeting()).into_markup()),
+        );
+        let options = options().parallel_threshold(8);
+        let html = markup.render_with(&options).unwrap();
+        assert_eq!(html.matches("Bonjour, Ada").count(), 64);
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod csp_tests {
+    use markup::*;
+
+    static WIDGET: ScopedStyle = ScopedStyle::new("widget", "p { margin: 0 }");
+
+    #[test]
+    fn test_nonce_applied_to_scripts_and_styles() {
+        let markup = Markup::Fragment(vec![
+            Markup::element("script")
+                .child(Markup::raw("init()"))
+                .into_markup(),
+            Markup::element("script")
+                .attr("src", "/app.js")
+                .attr("nonce", "stale")
+                .into_markup(),
+            Markup::self_element("link")
+                .attr("rel", "preload Stylesheet")
+                .attr("href", "/app.css")
+                .into_markup(),
+            Markup::self_element("link")
+                .attr("rel", "icon")
+                .attr("href", "/favicon.ico")
+                .into_markup(),
+            Markup::element("style")
+                .attr("nonce", "stale")
+                .child(Markup::raw("p {}"))
+                .into_markup(),
+        ]);
+        let options = RenderOptions::new().nonce("r4nd0m").unwrap();
+        assert_eq!(
+            markup.render_with(&options).unwrap(),
+            concat!(
+                r#"<script nonce="r4nd0m">init()</script><script src="/app.js" nonce="r4nd0m"></script>"#,
+                r#"<link rel="preload Stylesheet" href="/app.css" nonce="r4nd0m" /><link rel="icon" href="/favicon.ico" />"#,
+                r#"<style nonce="r4nd0m">p {}</style>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_nonce_applied_to_scoped_styles() {
+        let markup = WIDGET.scope(Markup::element("div"));
+        let rendered = markup
+            .render_with(&RenderOptions::new().nonce("abc").unwrap())
+            .unwrap();
+        assert!(rendered.starts_with(r#"<style nonce="abc">"#));
+    }
+
+    #[test]
+    fn test_inline_event_handler_rejected() {
+        let markup = Markup::element("div")
+            .child(Markup::self_element("img").attr("onerror", "alert(1)"))
+            .into_markup();
+        assert_eq!(
+            markup.render_with(&RenderOptions::new().nonce("abc").unwrap()),
+            Err(RenderError::InlineEventHandler {
+                tag: "img".to_string(),
+                attribute: "onerror".to_string()
+            })
+        );
+        assert!(markup.render_with(&RenderOptions::new()).is_ok());
+    }
+
+    #[test]
+    fn test_header_value() {
+        let options = RenderOptions::new().nonce("abc123").unwrap();
+        assert_eq!(
+            options.content_security_policy().unwrap(),
+            "default-src 'self'; script-src 'nonce-abc123' 'strict-dynamic'; style-src 'self' 'nonce-abc123'; object-src 'none'; base-uri 'none'"
+        );
+        assert!(csp::is_valid_nonce("abc+/="));
+        assert!(!csp::is_valid_nonce("abc'; script-src *"));
+        assert_eq!(
+            csp::header_value("abc'; script-src *"),
+            Err(csp::InvalidNonce("abc'; script-src *".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_nonce_rejected() {
+        assert!(RenderOptions::new().nonce("\"><script>").is_err());
+        assert!(RenderOptions::new().nonce("").is_err());
+    }
+}
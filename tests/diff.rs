@@ -0,0 +1,271 @@
+#[cfg(test)]
+mod diff_tests {
+    use markup::*;
+    use std::borrow::Cow;
+
+    fn item(key: &str) -> Markup {
+        Markup::element("li")
+            .attr("key", key.to_string())
+            .child(key.to_string())
+            .into_markup()
+    }
+
+    fn list(keys: &[&str]) -> Markup {
+        Markup::element("ul")
+            .child(keys.iter().map(|key| item(key)).collect::<Vec<_>>())
+            .into_markup()
+    }
+
+    fn assert_patches_to(old: &Markup, new: &Markup) -> Vec<Patch> {
+        let patches = Markup::diff(old, new);
+        let mut patched = old.clone();
+        patched.apply(&patches).unwrap();
+        assert!(
+            patched.semantic_eq(new),
+            "patches {patches:?} produced {}, expected {}",
+            patched.render(),
+            new.render()
+        );
+        patches
+    }
+
+    #[test]
+    fn test_identical_trees_have_no_patches() {
+        let page = list(&["a", "b"]);
+
+        assert!(Markup::diff(&page, &page.clone()).is_empty());
+        assert!(
+            Markup::diff(
+                &Markup::Fragment(vec![Markup::Text("a".into()), Markup::Text("b".into())]),
+                &Markup::Text("ab".into())
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_text_and_attribute_changes() {
+        let old = Markup::element("div")
+            .attr("id", "main")
+            .attr("title", "old")
+            .child(Markup::element("p").child("Hello"))
+            .into_markup();
+        let new = Markup::element("div")
+            .attr("id", "main")
+            .attr("hidden", true)
+            .child(Markup::element("p").child("Goodbye"))
+            .into_markup();
+
+        assert_eq!(
+            assert_patches_to(&old, &new),
+            [
+                Patch::RemoveAttribute {
+                    path: vec![],
+                    name: Cow::Borrowed("title"),
+                },
+                Patch::SetAttribute {
+                    path: vec![],
+                    name: Cow::Borrowed("hidden"),
+                    value: AttrValue::Bool(true),
+                },
+                Patch::UpdateText {
+                    path: vec![0, 0],
+                    text: Cow::Borrowed("Goodbye"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_tag_is_replaced() {
+        let old = Markup::element("div")
+            .child(Markup::element("b").child("x"))
+            .into_markup();
+        let new = Markup::element("div")
+            .child(Markup::element("i").child("x"))
+            .into_markup();
+
+        assert_eq!(
+            assert_patches_to(&old, &new),
+            [Patch::Replace {
+                path: vec![0],
+                node: Markup::element("i").child("x").into_markup(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unkeyed_children_are_matched_by_position() {
+        let li = |text: &'static str| Markup::element("li").child(text).into_markup();
+        let three = Markup::element("ul")
+            .child(vec![li("a"), li("b"), li("c")])
+            .into_markup();
+        let one = Markup::element("ul").child(li("a")).into_markup();
+        let two = Markup::element("ul")
+            .child(vec![li("a"), li("x")])
+            .into_markup();
+
+        assert_eq!(
+            assert_patches_to(&three, &one),
+            [
+                Patch::Remove { path: vec![2] },
+                Patch::Remove { path: vec![1] },
+            ]
+        );
+        assert_eq!(
+            assert_patches_to(&one, &two),
+            [Patch::Insert {
+                path: vec![1],
+                node: li("x"),
+            }]
+        );
+        assert_eq!(
+            assert_patches_to(&two, &three),
+            [
+                Patch::UpdateText {
+                    path: vec![1, 0],
+                    text: Cow::Borrowed("b"),
+                },
+                Patch::Insert {
+                    path: vec![2],
+                    node: li("c"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyed_reorder_moves_instead_of_rewriting() {
+        let old = list(&["a", "b", "c", "d", "e"]);
+        let new = list(&["e", "a", "b", "c", "d"]);
+
+        assert_eq!(
+            assert_patches_to(&old, &new),
+            [Patch::Move {
+                path: vec![4],
+                to: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_keyed_insert_and_remove() {
+        let old = list(&["a", "b", "c"]);
+        let new = list(&["a", "x", "c"]);
+
+        assert_eq!(
+            assert_patches_to(&old, &new),
+            [
+                Patch::Remove { path: vec![1] },
+                Patch::Insert {
+                    path: vec![1],
+                    node: item("x"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyed_permutations_round_trip() {
+        let keys = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut seed = 0x2545_f491_u64;
+
+        for _ in 0..200 {
+            let mut next = || {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as usize
+            };
+            let mut old: Vec<&str> = keys.iter().copied().filter(|_| next() % 4 != 0).collect();
+            let mut new: Vec<&str> = keys.iter().copied().filter(|_| next() % 4 != 0).collect();
+            for i in (1..old.len()).rev() {
+                old.swap(i, next() % (i + 1));
+            }
+            for i in (1..new.len()).rev() {
+                new.swap(i, next() % (i + 1));
+            }
+
+            let patches = assert_patches_to(&list(&old), &list(&new));
+            let moves = patches
+                .iter()
+                .filter(|patch| matches!(patch, Patch::Move { .. }))
+                .count();
+            assert!(moves < new.len().max(1), "{old:?} -> {new:?}: {patches:?}");
+        }
+    }
+
+    #[test]
+    fn test_mixed_keyed_and_unkeyed_children() {
+        let old = Markup::element("div")
+            .child(Markup::element("h2").child("Title"))
+            .child(item("a"))
+            .child(item("b"))
+            .into_markup();
+        let new = Markup::element("div")
+            .child(Markup::element("h2").child("Renamed"))
+            .child(item("b"))
+            .child(item("a"))
+            .child(Markup::element("p").child("Footer"))
+            .into_markup();
+
+        assert_patches_to(&old, &new);
+    }
+
+    #[test]
+    fn test_scoped_wrappers_are_transparent() {
+        static CARD: ScopedStyle = ScopedStyle::new("card", "p { color: red; }");
+
+        let old = Markup::element("main")
+            .child(CARD.scope(Markup::element("div").child(Markup::element("p").child("one"))))
+            .into_markup();
+        let new = Markup::element("main")
+            .child(CARD.scope(Markup::element("div").child(Markup::element("p").child("two"))))
+            .into_markup();
+
+        assert_eq!(
+            assert_patches_to(&old, &new),
+            [Patch::UpdateText {
+                path: vec![0, 0, 0],
+                text: Cow::Borrowed("two"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_paths() {
+        let mut page = Markup::element("div").child("text").into_markup();
+
+        assert_eq!(
+            page.apply(&[Patch::Remove { path: vec![3] }]),
+            Err(PatchError::InvalidPath(vec![3]))
+        );
+        assert_eq!(
+            page.apply(&[Patch::UpdateText {
+                path: vec![],
+                text: Cow::Borrowed("x"),
+            }]),
+            Err(PatchError::InvalidPath(vec![]))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_patches_serialize_to_tagged_json() {
+        let patches = Markup::diff(&list(&["a", "b"]), &list(&["b", "a"]));
+        let json = serde_json::to_value(&patches).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([{ "op": "move", "path": [0], "to": 1 }])
+        );
+        assert_eq!(serde_json::from_value::<Vec<Patch>>(json).unwrap(), patches);
+
+        let insert = Patch::Insert {
+            path: vec![0, 2],
+            node: item("z"),
+        };
+        let json = serde_json::to_string(&insert).unwrap();
+        assert_eq!(serde_json::from_str::<Patch>(&json).unwrap(), insert);
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod equality_tests {
+    use markup::*;
+    use std::borrow::Cow;
+    use std::collections::HashSet;
+
+    fn card(title: &'static str) -> Markup {
+        Markup::element("div")
+            .class("card")
+            .child(Markup::element("h2").child(title))
+            .into_markup()
+    }
+
+    #[test]
+    fn test_clone_and_structural_equality() {
+        let header = card("Header");
+        let page = Markup::Fragment(vec![header.clone(), header.clone()]);
+
+        assert_eq!(header, card("Header"));
+        assert_ne!(header, card("Footer"));
+        assert_eq!(page.render(), header.render().repeat(2));
+        assert_eq!(
+            Markup::element("a").attr("href", "/").into_markup(),
+            Markup::element("a")
+                .attr("href", "/".to_string())
+                .into_markup()
+        );
+    }
+
+    #[test]
+    fn test_hash_as_cache_key() {
+        let mut seen = HashSet::new();
+        assert!(seen.insert(card("Header")));
+        assert!(!seen.insert(card("Header")));
+        assert!(seen.insert(card("Footer")));
+    }
+
+    #[test]
+    fn test_semantic_equality() {
+        let built = Markup::self_element("input")
+            .attr("type", "text")
+            .attr("name", "q")
+            .attr("disabled", false)
+            .into_markup();
+        let reordered = Markup::self_element("input")
+            .attr("name", "q")
+            .attr("type", "text")
+            .into_markup();
+        assert_ne!(built, reordered);
+        assert!(built.semantic_eq(&reordered));
+
+        let split = Markup::element("p")
+            .child("Hello, ")
+            .child(Markup::Fragment(vec![
+                Markup::Text(Cow::Borrowed("wor")),
+                Markup::None,
+                Markup::Text(Cow::Borrowed("ld")),
+            ]))
+            .into_markup();
+        let merged = Markup::element("p").child("Hello, world").into_markup();
+        assert_ne!(split, merged);
+        assert!(split.semantic_eq(&merged));
+        assert!(!split.semantic_eq(&Markup::element("p").child("Hello").into_markup()));
+    }
+}
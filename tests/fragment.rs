@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod fragment_tests {
+    use markup::*;
+
+    static CARD: ScopedStyle = ScopedStyle::new("card", "p { color: red }");
+
+    fn page() -> Markup {
+        Markup::element("body")
+            .child(
+                Markup::element("main").id("content").child(
+                    Markup::element("ul")
+                        .id("list")
+                        .child(Markup::element("li").child("one"))
+                        .child(
+                            Markup::element("span")
+                                .id("count")
+                                .attr("hx-swap-oob", "true")
+                                .child("1"),
+                        ),
+                ),
+            )
+            .child(
+                Markup::element("aside")
+                    .child(
+                        Markup::element("span")
+                            .id("badge")
+                            .attr("hx-swap-oob", "outerHTML")
+                            .child("new"),
+                    )
+                    .child(
+                        Markup::element("div")
+                            .id("ignored")
+                            .attr("hx-swap-oob", false),
+                    ),
+            )
+            .child(
+                Markup::element("footer")
+                    .id("footer")
+                    .attr("hx-swap-oob", "true"),
+            )
+            .into_markup()
+    }
+
+    #[test]
+    fn test_render_fragment() {
+        assert_eq!(
+            page().render_fragment("badge").unwrap(),
+            r#"<span id="badge" hx-swap-oob="outerHTML">new</span>"#
+        );
+        assert_eq!(
+            page().render_fragment("list").unwrap(),
+            r#"<ul id="list"><li>one</li><span id="count" hx-swap-oob="true">1</span></ul>"#
+        );
+        assert_eq!(page().render_fragment("missing"), None);
+        assert_eq!(page().render_oob("missing"), None);
+    }
+
+    #[test]
+    fn test_fragment_keeps_scoped_styles() {
+        let markup = Markup::element("body")
+            .child(CARD.scope(Markup::element("div").child(Markup::element("p").id("note"))))
+            .into_markup();
+        assert_eq!(
+            markup.render_fragment("note").unwrap(),
+            format!(r#"<style>{}</style><p id="note"></p>"#, CARD.css())
+        );
+    }
+
+    #[test]
+    fn test_render_oob_in_document_order() {
+        assert_eq!(
+            page().render_oob("list").unwrap(),
+            concat!(
+                r#"<ul id="list"><li>one</li><span id="count" hx-swap-oob="true">1</span></ul>"#,
+                r#"<span id="badge" hx-swap-oob="outerHTML">new</span>"#,
+                r#"<footer id="footer" hx-swap-oob="true"></footer>"#,
+            )
+        );
+        assert_eq!(
+            page().render_oob("count").unwrap(),
+            concat!(
+                r#"<span id="count" hx-swap-oob="true">1</span>"#,
+                r#"<span id="badge" hx-swap-oob="outerHTML">new</span>"#,
+                r#"<footer id="footer" hx-swap-oob="true"></footer>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_oob_around_nested_target() {
+        let markup = Markup::element("body")
+            .child(
+                Markup::element("div")
+                    .id("panel")
+                    .attr("hx-swap-oob", "true")
+                    .child(Markup::element("p").id("status").child("ok"))
+                    .child(
+                        Markup::element("span")
+                            .id("badge")
+                            .attr("hx-swap-oob", "true"),
+                    ),
+            )
+            .child(
+                Markup::element("footer")
+                    .id("footer")
+                    .attr("hx-swap-oob", "true"),
+            )
+            .into_markup();
+
+        assert_eq!(
+            markup.render_oob("status").unwrap(),
+            concat!(
+                r#"<p id="status">ok</p>"#,
+                r#"<span id="badge" hx-swap-oob="true"></span>"#,
+                r#"<footer id="footer" hx-swap-oob="true"></footer>"#,
+            )
+        );
+        assert_eq!(
+            markup.render_oob("panel").unwrap(),
+            concat!(
+                r#"<div id="panel" hx-swap-oob="true"><p id="status">ok</p><span id="badge" hx-swap-oob="true"></span></div>"#,
+                r#"<footer id="footer" hx-swap-oob="true"></footer>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_fragment_respects_nonce() {
+        let markup = Markup::element("div")
+            .child(
+                Markup::element("button")
+                    .id("save")
+                    .attr("onclick", "save()"),
+            )
+            .into_markup();
+        let options = RenderOptions::new().nonce("abc").unwrap();
+        assert!(markup.render_fragment_with("save", &options).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod htmx_tests {
+    use markup::htmx::*;
+    use markup::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_request_attributes() {
+        let markup = Markup::element("button")
+            .hx_post("/items?sort=name&page=2")
+            .hx_target(Target::Closest(Selector::css("tr")))
+            .hx_swap(Swap::OuterHtml)
+            .hx_confirm("Delete \"item\"?")
+            .child("Delete")
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<button hx-post="/items?sort=name&amp;page=2" hx-target="closest tr" hx-swap="outerHTML" hx-confirm="Delete &quot;item&quot;?">Delete</button>"#
+        );
+    }
+
+    #[test]
+    fn test_triggers() {
+        let search = Trigger::keyup()
+            .filter("key=='Enter'")
+            .changed()
+            .delay(Duration::from_millis(500))
+            .from(Selector::id("search"));
+        assert_eq!(
+            search.to_string(),
+            "keyup[key=='Enter'] changed delay:500ms from:#search"
+        );
+
+        let markup = Markup::self_element("input")
+            .hx_get("/search")
+            .hx_triggers([
+                search,
+                Trigger::every(Duration::from_secs(2)).queue(Queue::Last),
+                Trigger::load().once(),
+            ])
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<input hx-get="/search" hx-trigger="keyup[key==&apos;Enter&apos;] changed delay:500ms from:#search, every 2000ms queue:last, load once" />"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid htmx trigger event name")]
+    fn test_invalid_event_name() {
+        Trigger::event("click once");
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(Selector::id("main").as_str(), "#main");
+        assert_eq!(Selector::id("1st row").as_str(), r"#\31 st\ row");
+        assert_eq!(Selector::class("w-1/2").as_str(), r".w-1\/2");
+        assert_eq!(Target::Next(None).to_string(), "next");
+        assert_eq!(
+            Target::Find(Selector::class("spinner")).to_string(),
+            "find .spinner"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_hx_vals() {
+        let markup = Markup::element("div")
+            .hx_vals(&serde_json::json!({ "id": 7, "note": "<a & b>" }))
+            .unwrap()
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<div hx-vals="{&quot;id&quot;:7,&quot;note&quot;:&quot;&lt;a &amp; b&gt;&quot;}"></div>"#
+        );
+
+        assert!(Markup::element("div").hx_vals(&[1, 2]).is_err());
+    }
+}
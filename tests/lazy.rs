@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod lazy_tests {
+    use markup::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_lazy_writes_escaped_and_raw() {
+        let rows = vec![("Tea & cake", 3.5), ("<script>", 1.25)];
+        let markup = Markup::element("table")
+            .child(Markup::lazy(move |out| {
+                for (name, price) in &rows {
+                    out.raw("<tr><td>");
+                    out.text(name);
+                    out.raw("</td><td>");
+                    write!(out, "{price:.2} <EUR>")?;
+                    out.raw("</td></tr>");
+                }
+                Ok(())
+            }))
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            "<table><tr><td>Tea &amp; cake</td><td>3.50 &lt;EUR&gt;</td></tr><tr><td>&lt;script&gt;</td><td>1.25 &lt;EUR&gt;</td></tr></table>"
+        );
+    }
+
+    #[test]
+    fn test_lazy_sees_context_and_streams() {
+        struct Currency(&'static str);
+
+        let price = |amount: u32| {
+            Markup::lazy(move |out| {
+                let currency = out.context().get::<Currency>().map_or("$", |c| c.0);
+                write!(out, "{currency}{amount}")
+            })
+        };
+        let markup = Markup::element("p")
+            .child(price(5))
+            .child(Markup::provide(Currency("€"), price(7)))
+            .into_markup();
+        let options = RenderOptions::new().context(RenderContext::new().with(Currency("£")));
+
+        let html = markup.render_with(&options).unwrap();
+        assert_eq!(html, "<p>£5€7</p>");
+        let chunks: String = markup
+            .into_chunks_with(4, options)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(chunks, html);
+    }
+
+    #[test]
+    fn test_lazy_format_error_fails_render() {
+        struct Broken;
+
+        impl std::fmt::Display for Broken {
+            fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                Err(std::fmt::Error)
+            }
+        }
+
+        let markup = Markup::element("p")
+            .child(Markup::lazy(|out| write!(out, "{Broken}")))
+            .into_markup();
+        assert_eq!(
+            markup.render_with(&RenderOptions::new()),
+            Err(RenderError::Format)
+        );
+        let chunks: Vec<_> = markup.into_chunks(4).collect();
+        assert_eq!(chunks.last(), Some(&Err(RenderError::Format)));
+    }
+
+    #[test]
+    fn test_format_args_into_markup() {
+        let user = "<Ada>";
+        let markup = Markup::element("p")
+            .child(format_args!("Hello, {user} & {}", 42))
+            .child(format_args!("!"))
+            .into_markup();
+        assert_eq!(markup.render(), "<p>Hello, &lt;Ada&gt; &amp; 42!</p>");
+    }
+}
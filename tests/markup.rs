@@ -79,6 +79,49 @@ mod markup_tests {
         );
     }
 
+    #[test]
+    fn test_escaping_across_word_boundaries() {
+        let markup = Markup::Text(Cow::Borrowed("héllo wörld & <ünïcode> \"quoted\" 'x'&"));
+        assert_eq!(
+            markup.render(),
+            "héllo wörld &amp; &lt;ünïcode&gt; &quot;quoted&quot; &apos;x&apos;&amp;"
+        );
+
+        let long = "a".repeat(13) + "<" + &"é".repeat(9) + ">";
+        let markup = Markup::Text(Cow::Owned(long));
+        assert_eq!(
+            markup.render(),
+            "a".repeat(13) + "&lt;" + &"é".repeat(9) + "&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_into_reuses_buffer() {
+        let markup = Markup::element("p")
+            .attr("title", "a & b")
+            .child("<hi>")
+            .into_markup();
+        let mut buffer = String::from("prefix:");
+        markup.render_into(&mut buffer);
+        assert_eq!(buffer, r#"prefix:<p title="a &amp; b">&lt;hi&gt;</p>"#);
+
+        buffer.clear();
+        markup.render_into(&mut buffer);
+        assert_eq!(buffer, markup.render());
+    }
+
+    #[test]
+    fn test_size_hint_is_upper_bound() {
+        let markup = Markup::element("div")
+            .attr("class", "a 'quoted' <value>")
+            .attr("hidden", true)
+            .child(Markup::self_element("br"))
+            .child("Tom & Jerry")
+            .child(Markup::raw("<b>raw</b>"))
+            .into_markup();
+        assert_eq!(markup.size_hint(), markup.render().len());
+    }
+
     #[test]
     fn test_raw_rendering() {
         let markup = Markup::raw("<script>alert('XSS')</script>");
@@ -96,7 +139,7 @@ mod markup_tests {
 
     #[test]
     fn test_map_rendering() {
-        let numbers = vec![1, 2, 3];
+        let numbers = [1, 2, 3];
         let markup = numbers.iter().map(|n| format!("{}", n)).into_markup();
         assert_eq!(markup.render(), "123");
     }
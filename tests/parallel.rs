@@ -0,0 +1,94 @@
+#![cfg(feature = "rayon")]
+
+#[cfg(test)]
+mod parallel_tests {
+    use markup::*;
+
+    static ROW: ScopedStyle = ScopedStyle::new("row", "td { padding: 0 }");
+    static CELL: ScopedStyle = ScopedStyle::new("cell", "& { color: red }");
+
+    fn report(rows: usize) -> Markup {
+        Markup::Fragment(vec![
+            Markup::element("head")
+                .child(Markup::element("title").child("Report"))
+                .into_markup(),
+            Markup::element("table")
+                .child(
+                    (0..rows)
+                        .map(|i| {
+                            let row = Markup::element("tr")
+                                .child(Markup::element("td").child(format!("{i} & <{i}>")))
+                                .child(Markup::self_element("input").attr("disabled", i % 2 == 0));
+                            if i == rows / 2 {
+                                CELL.scope(row)
+                            } else {
+                                ROW.scope(row)
+                            }
+                        })
+                        .into_markup(),
+                )
+                .into_markup(),
+        ])
+    }
+
+    #[test]
+    fn test_parallel_output_is_identical() {
+        let markup = report(5_000);
+        let serial = RenderOptions::new().parallel_threshold(usize::MAX);
+        let parallel = RenderOptions::new().parallel_threshold(16);
+
+        let expected = markup.render_with(&serial).unwrap();
+        assert_eq!(markup.render_with(&parallel).unwrap(), expected);
+        assert_eq!(markup.render(), expected);
+        assert!(expected.starts_with(&format!(
+            "<head><title>Report</title><style>{}{}</style></head>",
+            ROW.css(),
+            CELL.css()
+        )));
+    }
+
+    #[test]
+    fn test_parallel_errors_reported() {
+        let markup = Markup::Fragment(
+            (0..100)
+                .map(|i| {
+                    let button = Markup::element("button");
+                    if i == 70 {
+                        button.attr("onclick", "go()")
+                    } else {
+                        button
+                    }
+                    .into_markup()
+                })
+                .collect(),
+        );
+        let options = RenderOptions::new()
+            .nonce("n")
+            .unwrap()
+            .parallel_threshold(4);
+        assert_eq!(
+            markup.render_with(&options),
+            Err(RenderError::InlineEventHandler {
+                tag: "button".to_string(),
+                attribute: "onclick".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parallel_placeholder_position() {
+        let mut children: Vec<Markup> = (0..50)
+            .map(|i| Markup::Text(format!("{i},").into()))
+            .collect();
+        children.insert(30, Markup::ScopedStyles);
+        children.push(ROW.scope(Markup::element("p")));
+        let markup = Markup::Fragment(children);
+
+        let serial = RenderOptions::new().parallel_threshold(usize::MAX);
+        let parallel = RenderOptions::new().parallel_threshold(2);
+        assert_eq!(
+            markup.render_with(&parallel).unwrap(),
+            markup.render_with(&serial).unwrap()
+        );
+    }
+}
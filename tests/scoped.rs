@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod scoped_tests {
+    use markup::*;
+
+    static CARD: ScopedStyle = ScopedStyle::new(
+        "card",
+        "/* card */ & { padding: 1rem } .title, &:hover > p { color: red } @media (max-width: 600px) { .title { font-size: 1rem } }",
+    );
+    static BADGE: ScopedStyle = ScopedStyle::new("badge", "span { color: blue }");
+
+    #[test]
+    fn test_selector_rewriting() {
+        let class = CARD.class();
+        assert!(class.starts_with("card-"));
+        assert_eq!(
+            CARD.css(),
+            format!(
+                ".{class}{{padding: 1rem}}.{class} .title,.{class}:hover > p{{color: red}}@media (max-width: 600px){{.{class} .title{{font-size: 1rem}}}}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_statement_at_rules_kept_apart() {
+        static SHEET: ScopedStyle = ScopedStyle::new(
+            "sheet",
+            "@charset \"utf-8\"; @import url(\"a;b.css\"); @layer base, theme; p { margin: 0 } @layer base { a { color: red } }",
+        );
+        let class = SHEET.class();
+        assert_eq!(
+            SHEET.css(),
+            format!(
+                r#"@charset "utf-8";@import url("a;b.css");@layer base, theme;.{class} p{{margin: 0}}@layer base{{.{class} a{{color: red}}}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_ampersand_in_attribute_selector() {
+        static LINKS: ScopedStyle = ScopedStyle::new(
+            "links",
+            r#"a[title="R&D"] { color: red } &[data-x='a&b'] > p { margin: 0 }"#,
+        );
+        let class = LINKS.class();
+        assert_eq!(
+            LINKS.css(),
+            format!(
+                r#".{class} a[title="R&D"]{{color: red}}.{class}[data-x='a&b'] > p{{margin: 0}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_comment_markers_in_strings() {
+        static QUOTES: ScopedStyle = ScopedStyle::new(
+            "quotes",
+            r#"p::before { content: "/* x */ }" } /* note */ q { quotes: '/*' '*/' }"#,
+        );
+        let class = QUOTES.class();
+        assert_eq!(
+            QUOTES.css(),
+            format!(
+                r#".{class} p::before{{content: "/* x */ }}"}}.{class} q{{quotes: '/*' '*/'}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_scope_class_added_to_root() {
+        let markup = Markup::element("div")
+            .child(CARD.scope(Markup::element("section").class("box")))
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            format!(
+                r#"<style>{}</style><div><section class="box {}"></section></div>"#,
+                CARD.css(),
+                CARD.class()
+            )
+        );
+    }
+
+    #[test]
+    fn test_styles_collected_into_head() {
+        let markup = Markup::element("html")
+            .child(Markup::element("head").child(Markup::element("title").child("Page")))
+            .child(
+                Markup::element("body")
+                    .child(CARD.scope(Markup::element("div")))
+                    .child(BADGE.scope(Markup::element("div")))
+                    .child(CARD.scope(Markup::element("div"))),
+            )
+            .into_markup();
+        let expected = format!(
+            r#"<html><head><title>Page</title><style>{}{}</style></head><body><div class="{card}"></div><div class="{}"></div><div class="{card}"></div></body></html>"#,
+            CARD.css(),
+            BADGE.css(),
+            BADGE.class(),
+            card = CARD.class(),
+        );
+        assert_eq!(markup.render(), expected);
+    }
+
+    #[test]
+    fn test_styles_placeholder() {
+        let markup = Markup::Fragment(vec![
+            Markup::element("p").child("before").into_markup(),
+            Markup::ScopedStyles,
+            BADGE.scope(Markup::element("span")),
+        ]);
+        assert_eq!(
+            markup.render(),
+            format!(
+                r#"<p>before</p><style>{}</style><span class="{}"></span>"#,
+                BADGE.css(),
+                BADGE.class()
+            )
+        );
+    }
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod serde_tests {
+    use markup::*;
+    use std::time::Duration;
+
+    static CARD: ScopedStyle = ScopedStyle::new("card", ".title { color: red; }");
+
+    fn round_trip(markup: &Markup) -> Markup {
+        let json = serde_json::to_string(markup).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn page() -> Markup {
+        Markup::element("html")
+            .child(Markup::element("head").child(Markup::ScopedStyles))
+            .child(
+                Markup::element("body")
+                    .child(
+                        CARD.scope(
+                            Markup::element("div")
+                                .child(Markup::element("h2").class("title").child("A & B")),
+                        ),
+                    )
+                    .child(
+                        Markup::element("form")
+                            .child(
+                                Markup::self_element("input")
+                                    .attr("name", "q")
+                                    .attr("required", true)
+                                    .attr("disabled", false),
+                            )
+                            .child(Markup::raw("<!-- raw -->")),
+                    )
+                    .child(vec!["one", "two"])
+                    .child(None::<Markup>),
+            )
+            .into_markup()
+    }
+
+    #[test]
+    fn test_round_trip_renders_identically() {
+        let markup = page();
+        let restored = round_trip(&markup);
+
+        assert_eq!(restored.render(), markup.render());
+        assert_eq!(restored, markup);
+    }
+
+    #[test]
+    fn test_json_representation() {
+        let markup = Markup::element("a")
+            .attr("href", "/")
+            .attr("hidden", true)
+            .child("Home")
+            .into_markup();
+
+        assert_eq!(
+            serde_json::to_value(&markup).unwrap(),
+            serde_json::json!({
+                "regular_tag": {
+                    "tag": "a",
+                    "attributes": [
+                        { "name": "href", "value": "/" },
+                        { "name": "hidden", "value": true }
+                    ],
+                    "children": [{ "text": "Home" }]
+                }
+            })
+        );
+        assert_eq!(serde_json::to_value(Markup::None).unwrap(), "none");
+    }
+
+    #[test]
+    fn test_deserialize_from_json() {
+        let json = r#"{ "regular_tag": {
+            "tag": "p",
+            "attributes": [{ "name": "class", "value": "note" }],
+            "children": [{ "text": "<hi>" }, { "self_closing_tag": { "tag": "br", "attributes": [] } }]
+        } }"#;
+        let markup: Markup = serde_json::from_str(json).unwrap();
+
+        assert_eq!(markup.render(), "<p class=\"note\">&lt;hi&gt;<br /></p>");
+    }
+
+    #[test]
+    fn test_cached_round_trip_keeps_key_and_content() {
+        let markup = Markup::cached("serde-sidebar", || Markup::element("nav").child("Links"))
+            .ttl(Duration::from_secs(60))
+            .into_markup();
+        let restored = round_trip(&markup);
+
+        let Markup::Cached(cached) = &restored else {
+            panic!("expected a cached node, got {restored:?}");
+        };
+        assert_eq!(cached.key, "serde-sidebar");
+        assert_eq!(cached.ttl, Some(Duration::from_secs(60)));
+        assert_eq!(cached.build().render(), "<nav>Links</nav>");
+        assert_eq!(restored.render(), markup.render());
+    }
+
+    #[test]
+    fn test_invalid_attribute_value_is_rejected() {
+        let json = r#"{ "self_closing_tag": { "tag": "br", "attributes": [{ "name": "x", "value": 1 }] } }"#;
+
+        assert!(serde_json::from_str::<Markup>(json).is_err());
+    }
+
+    #[test]
+    fn test_invalid_names_are_rejected() {
+        let invalid = [
+            r#"{ "regular_tag": { "tag": "p><script>alert(1)</script", "attributes": [], "children": [] } }"#,
+            r#"{ "self_closing_tag": { "tag": "", "attributes": [] } }"#,
+            r#"{ "self_closing_tag": { "tag": "img", "attributes": [{ "name": "src=x onerror", "value": "alert(1)" }] } }"#,
+            r#"{ "self_closing_tag": { "tag": "img", "attributes": [{ "name": "a><script", "value": true }] } }"#,
+        ];
+        for json in invalid {
+            assert!(serde_json::from_str::<Markup>(json).is_err(), "{json}");
+        }
+
+        let json = r#"{ "regular_tag": {
+            "tag": "my-widget",
+            "attributes": [{ "name": "hx-on::after-request", "value": "done()" }, { "name": "@click", "value": "go" }],
+            "children": []
+        } }"#;
+        let markup: Markup = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            markup.render(),
+            r#"<my-widget hx-on::after-request="done()" @click="go"></my-widget>"#
+        );
+    }
+}
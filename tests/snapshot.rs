@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod snapshot_tests {
+    use markup::testing;
+    use markup::*;
+    use std::path::PathBuf;
+    use std::{env, fs, panic};
+
+    fn page() -> Markup {
+        Markup::element("html")
+            .child(
+                Markup::element("head")
+                    .child(Markup::element("title").child("Snapshot & Test"))
+                    .child(Markup::self_element("meta").attr("charset", "utf-8")),
+            )
+            .child(
+                Markup::element("body").child(
+                    Markup::element("main")
+                        .attr("id", "content")
+                        .class("page")
+                        .child(Markup::element("h1").child("Welcome"))
+                        .child(Markup::element("pre").child("line one\n  line two"))
+                        .child(
+                            Markup::element("ul").child(
+                                ["Home", "About"]
+                                    .map(|item| Markup::element("li").child(item).into_markup())
+                                    .to_vec(),
+                            ),
+                        ),
+                ),
+            )
+            .into_markup()
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("markup-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_page_snapshot() {
+        assert_snapshot!(page());
+    }
+
+    #[test]
+    fn test_named_snapshot() {
+        testing::assert_snapshot!(
+            "navigation",
+            Markup::element("nav")
+                .child(Markup::element("a").attr("href", "/").child("Home"))
+                .into_markup()
+        );
+    }
+
+    #[test]
+    fn test_pretty_html() {
+        assert_eq!(
+            testing::pretty_html("<div b=\"2\" a='1'><p>x &amp; y</p><br><span></span></div>"),
+            "<div a=\"1\" b=\"2\">\n  <p>x &amp; y</p>\n  <br>\n  <span></span>\n</div>\n"
+        );
+        assert_eq!(
+            testing::pretty_html("<pre><b>a</b>\n  b</pre><script>if (a < b) {}</script>"),
+            "<pre><b>a</b>\n  b</pre>\n<script>if (a < b) {}</script>\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_snapshot_is_written() {
+        let directory = scratch("missing");
+        testing::check_snapshot(&directory, "card", &"<p>card</p>", false, false);
+
+        assert_eq!(
+            fs::read_to_string(directory.join("card.html")).unwrap(),
+            "<p>card</p>\n"
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_missing_snapshot_fails_on_ci() {
+        let directory = scratch("ci");
+        let dir = directory.clone();
+        let error = panic::catch_unwind(move || {
+            testing::check_snapshot(&dir, "card", &"<p>card</p>", false, true)
+        })
+        .unwrap_err();
+        let message = error.downcast_ref::<String>().unwrap();
+
+        assert!(message.starts_with("snapshot `card` is missing"));
+        assert!(!directory.join("card.html").exists());
+
+        testing::check_snapshot(&directory, "card", &"<p>card</p>", true, true);
+        assert!(directory.join("card.html").exists());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_mismatch_fails_with_line_diff() {
+        let directory = scratch("mismatch");
+        testing::check_snapshot(
+            &directory,
+            "list",
+            &"<ul><li>a</li><li>b</li></ul>",
+            false,
+            false,
+        );
+
+        let dir = directory.clone();
+        let error = panic::catch_unwind(move || {
+            testing::check_snapshot(&dir, "list", &"<ul><li>a</li><li>c</li></ul>", false, false)
+        })
+        .unwrap_err();
+        let message = error.downcast_ref::<String>().unwrap();
+
+        assert!(message.starts_with("snapshot `list` does not match"));
+        assert!(message.contains(testing::UPDATE_SNAPSHOTS));
+        assert!(
+            message.ends_with("  <ul>\n    <li>a</li>\n-   <li>b</li>\n+   <li>c</li>\n  </ul>\n")
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_update_overwrites_snapshot() {
+        let directory = scratch("update");
+        testing::check_snapshot(&directory, "title", &"<h1>Old</h1>", false, false);
+        testing::check_snapshot(&directory, "title", &"<h1>New</h1>", true, false);
+
+        assert_eq!(
+            fs::read_to_string(directory.join("title.html")).unwrap(),
+            "<h1>New</h1>\n"
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "sri")]
+
+#[cfg(test)]
+mod sri_tests {
+    use markup::sri::{self, Algorithm, Mismatch};
+    use markup::*;
+
+    fn asset_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("mkup-sri-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("js")).unwrap();
+        std::fs::write(dir.join("js/app.js"), "alert('hi');").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            sri::integrity(b"alert('Hello, world.');", Algorithm::Sha384),
+            "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO"
+        );
+        assert_eq!(
+            sri::integrity(b"", Algorithm::Sha256),
+            "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+        );
+    }
+
+    #[test]
+    fn test_script_and_stylesheet_tags() {
+        let markup = Markup::Fragment(vec![
+            sri::script("/app.js", b"", Algorithm::Sha256).into_markup(),
+            sri::stylesheet("/site.css", b"", Algorithm::Sha256).into_markup(),
+        ]);
+        assert_eq!(
+            markup.render(),
+            r#"<script src="/app.js" integrity="sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=" crossorigin="anonymous"></script><link rel="stylesheet" href="/site.css" integrity="sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=" crossorigin="anonymous" />"#
+        );
+    }
+
+    #[test]
+    fn test_verify_tree_against_files() {
+        let dir = asset_dir("verify");
+        let good =
+            sri::script_from_file("/js/app.js", dir.join("js/app.js"), Algorithm::Sha512).unwrap();
+        let markup = Markup::element("head")
+            .child(good)
+            .child(
+                Markup::element("script")
+                    .attr("src", "https://cdn.example.com/x.js")
+                    .attr("integrity", "sha384-bogus"),
+            )
+            .into_markup();
+        assert!(sri::verify(&markup, &dir).is_ok());
+
+        let stale = Markup::Fragment(vec![
+            sri::script("/js/app.js?v=2", b"old contents", Algorithm::Sha384).into_markup(),
+            sri::script("/js/gone.js", b"", Algorithm::Sha384).into_markup(),
+        ]);
+        let mismatches = sri::verify(&stale, &dir).unwrap_err();
+        assert!(matches!(&mismatches[0], Mismatch::Digest { url, .. } if url == "/js/app.js?v=2"));
+        assert!(matches!(&mismatches[1], Mismatch::Missing { url, .. } if url == "/js/gone.js"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_uses_strongest_algorithm() {
+        let dir = asset_dir("strongest");
+        let bytes = std::fs::read(dir.join("js/app.js")).unwrap();
+        let sha256 = sri::integrity(&bytes, Algorithm::Sha256);
+        let sha384 = sri::integrity(&bytes, Algorithm::Sha384);
+        let script = |integrity: String| {
+            Markup::element("script")
+                .attr("src", "/js/app.js")
+                .attr("integrity", integrity)
+                .into_markup()
+        };
+
+        assert!(sri::verify(&script(format!("sha1-unknown {sha384}")), &dir).is_ok());
+        assert!(sri::verify(&script(format!("{sha256} sha384-stale")), &dir).is_err());
+        let mismatches = sri::verify(&script("sha1-unknown md5-x".to_string()), &dir).unwrap_err();
+        assert!(matches!(&mismatches[0], Mismatch::Malformed { .. }));
+
+        let outside = Markup::element("script")
+            .attr("src", "/js/../../secret.js")
+            .attr("integrity", sha384);
+        let mismatches = sri::verify(&outside.into_markup(), &dir).unwrap_err();
+        assert!(
+            matches!(&mismatches[0], Mismatch::OutsideRoot { url } if url == "/js/../../secret.js")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod sse_tests {
+    use markup::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_event_fields() {
+        let event = sse::Event::new(Markup::element("li").child("new"))
+            .event("message")
+            .id("42")
+            .retry(Duration::from_secs(3));
+        assert_eq!(
+            event.encode(),
+            "event: message\nid: 42\nretry: 3000\ndata: <li>new</li>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_multiline_data() {
+        let event = sse::Event::data("<pre>a\nb\r\nc\rd</pre>");
+        assert_eq!(
+            event.encode(),
+            "data: <pre>a\ndata: b\ndata: c\ndata: d</pre>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_fields_cannot_break_framing() {
+        let event = sse::Event::data("x").event("up\ndate").id("1\r\n\0");
+        assert_eq!(event.encode(), "event: update\nid: 1\ndata: x\n\n");
+        assert_eq!(sse::comment("ping"), ": ping\n\n");
+        assert_eq!(sse::comment(""), ":\n\n");
+    }
+
+    #[test]
+    fn test_encode_iterator_of_updates() {
+        let body: String = (1..=2)
+            .map(|i| Markup::element("p").child(format!("{i}")))
+            .map(sse::encode)
+            .collect();
+        assert_eq!(body, "data: <p>1</p>\n\ndata: <p>2</p>\n\n");
+    }
+}
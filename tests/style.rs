@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod style_tests {
+    use markup::style::{Align, Display, StyleError, hex, percent, px, rem};
+    use markup::*;
+
+    #[test]
+    fn test_style_builder() {
+        let style = Style::new()
+            .margin(px(0))
+            .display(Display::Flex)
+            .justify_content(Align::SpaceBetween)
+            .width(percent(50))
+            .font_size(rem(1.25))
+            .color(hex(0x336699));
+        assert_eq!(
+            style.to_string(),
+            "margin: 0px; display: flex; justify-content: space-between; width: 50%; font-size: 1.25rem; color: #336699"
+        );
+    }
+
+    #[test]
+    fn test_font_family_escaping() {
+        let markup = Markup::element("p")
+            .style(Style::new().font_family(["Arial", "Evil\"; color: red"]))
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<p style="font-family: &quot;Arial&quot;, &quot;Evil\&quot;; color: red&quot;"></p>"#
+        );
+        assert_eq!(
+            Style::new()
+                .font_family(["Arial", "sans-serif", "System-UI"])
+                .to_string(),
+            r#"font-family: "Arial", sans-serif, System-UI"#
+        );
+    }
+
+    #[test]
+    fn test_property_validation() {
+        assert!(Style::new().property("--accent", "rgb(0, 0, 0)").is_ok());
+        assert_eq!(
+            Style::new()
+                .property("color", "red; background: url(x)")
+                .unwrap_err(),
+            StyleError::InvalidValue {
+                property: "color".to_string(),
+                value: "red; background: url(x)".to_string(),
+            }
+        );
+        assert!(Style::new().property("co lor", "red").is_err());
+        assert!(
+            Style::new()
+                .property("width", "expression(alert(1))")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_style_merges_with_existing_value() {
+        let markup = Markup::element("div")
+            .attr("style", "margin: 4px; color: red")
+            .style(Style::new().margin(px(0)).display(Display::Block))
+            .into_markup();
+        assert_eq!(
+            markup.render(),
+            r#"<div style="margin: 0px; color: red; display: block"></div>"#
+        );
+    }
+}
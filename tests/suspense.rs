@@ -0,0 +1,135 @@
+mod common;
+
+#[cfg(test)]
+mod suspense_tests {
+    use crate::common::{Noop, yield_times};
+    use markup::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+
+    /// Collects every chunk, polling until the stream ends.
+    fn collect(mut stream: Streaming) -> Vec<String> {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let mut chunks = Vec::new();
+        for _ in 0..100 {
+            match stream.poll_chunk(&mut cx) {
+                Poll::Ready(Some(chunk)) => chunks.push(chunk.unwrap()),
+                Poll::Ready(None) => return chunks,
+                Poll::Pending => {}
+            }
+        }
+        panic!("stream did not end");
+    }
+
+    fn widget(name: &'static str, polls: usize) -> Markup {
+        Markup::suspense(Markup::element("p").child("Loading..."), async move {
+            yield_times(polls).await;
+            Markup::element("p").child(name)
+        })
+    }
+
+    fn page() -> Markup {
+        Markup::element("body")
+            .child(Markup::element("h1").child("Shell"))
+            .child(widget("slow", 4))
+            .child(widget("fast", 1))
+            .into_markup()
+    }
+
+    fn swap(id: &str) -> String {
+        format!(r#"("{id}")</script>"#)
+    }
+
+    #[test]
+    fn test_shell_first_then_resolved_content() {
+        let chunks = collect(page().into_stream(64 * 1024));
+        assert_eq!(
+            chunks[0],
+            concat!(
+                "<body><h1>Shell</h1>",
+                r#"<template id="suspense-0"></template><p>Loading...</p><!--/suspense-0-->"#,
+                r#"<template id="suspense-1"></template><p>Loading...</p><!--/suspense-1-->"#,
+                "</body>",
+            )
+        );
+
+        let rest = chunks[1..].concat();
+        let fast = rest
+            .find(r#"<template id="suspense-1-content"><p>fast</p></template><script>"#)
+            .unwrap();
+        let slow = rest
+            .find(r#"<template id="suspense-0-content"><p>slow</p></template><script>"#)
+            .unwrap();
+        assert!(fast < slow);
+        assert!(rest.contains(&swap("suspense-0")));
+        assert!(rest.contains(&swap("suspense-1")));
+    }
+
+    #[test]
+    fn test_other_renderers() {
+        assert_eq!(
+            page().render(),
+            "<body><h1>Shell</h1><p>Loading...</p><p>Loading...</p></body>"
+        );
+
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(page().render_async());
+        let html = loop {
+            if let Poll::Ready(html) = future.as_mut().poll(&mut cx) {
+                break html;
+            }
+        };
+        assert_eq!(html, "<body><h1>Shell</h1><p>slow</p><p>fast</p></body>");
+    }
+
+    #[test]
+    fn test_cloned_tree_renders_twice() {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let page = page();
+        let copy = page.clone();
+
+        let mut future = pin!(page.render_async());
+        let html = loop {
+            if let Poll::Ready(html) = future.as_mut().poll(&mut cx) {
+                break html;
+            }
+        };
+        assert_eq!(html, "<body><h1>Shell</h1><p>slow</p><p>fast</p></body>");
+
+        let streamed = collect(copy.clone().into_stream(64 * 1024)).concat();
+        assert!(streamed.contains(r#"<template id="suspense-0-content"><p>slow</p></template>"#));
+        assert!(streamed.contains(r#"<template id="suspense-1-content"><p>fast</p></template>"#));
+
+        let mut future = pin!(copy.render_async());
+        let again = loop {
+            if let Poll::Ready(html) = future.as_mut().poll(&mut cx) {
+                break html;
+            }
+        };
+        assert_eq!(again, html);
+    }
+
+    #[test]
+    fn test_script_carries_nonce() {
+        let options = RenderOptions::new().nonce("abc").unwrap();
+        let html = collect(widget("done", 0).into_stream_with(1024, options)).concat();
+        assert!(html.contains(r#"</template><script nonce="abc">"#));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "http-body")]
+    async fn test_body_streams_boundaries() {
+        use http_body_util::BodyExt;
+
+        let body = MarkupBody::new(page());
+        let html = String::from_utf8(body.collect().await.unwrap().to_bytes().to_vec()).unwrap();
+        assert!(html.starts_with("<body><h1>Shell</h1>"));
+        assert!(html.contains(r#"<template id="suspense-0-content"><p>slow</p></template>"#));
+        assert!(html.contains(r#"<template id="suspense-1-content"><p>fast</p></template>"#));
+    }
+}
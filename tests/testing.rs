@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod testing_tests {
+    use markup::testing::{self, Element, Node};
+    use markup::*;
+    use std::panic;
+
+    fn page() -> Markup {
+        Markup::element("div")
+            .attr("class", "card")
+            .attr("id", "main")
+            .child(Markup::element("h1").child("Tom & Jerry"))
+            .child(Markup::self_element("input").attr("disabled", true))
+            .into_markup()
+    }
+
+    fn failure_message(run: impl FnOnce() + panic::UnwindSafe) -> String {
+        let error = panic::catch_unwind(run).unwrap_err();
+        error.downcast_ref::<String>().cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_ignores_attribute_order_whitespace_and_entity_spelling() {
+        assert_html_eq!(
+            page(),
+            r#"
+            <div id='main' class="card">
+                <h1>  Tom &#38; Jerry </h1>
+                <input disabled="">
+            </div>
+            "#
+        );
+        assert_html_eq!("<p>a</p>", String::from("<P>a</P>"), "tag case");
+    }
+
+    #[test]
+    fn test_parse_builds_normalized_tree() {
+        assert_eq!(
+            testing::parse("<!DOCTYPE html><ul b=2 a=1>\n  <li>x<br/>y</li><!-- note --></ul>"),
+            [
+                Node::Doctype("doctype html".to_string()),
+                Node::Element(Element {
+                    tag: "ul".to_string(),
+                    attributes: vec![
+                        ("a".to_string(), "1".to_string()),
+                        ("b".to_string(), "2".to_string())
+                    ],
+                    children: vec![
+                        Node::Element(Element {
+                            tag: "li".to_string(),
+                            attributes: vec![],
+                            children: vec![
+                                Node::Text("x".to_string()),
+                                Node::Element(Element {
+                                    tag: "br".to_string(),
+                                    attributes: vec![],
+                                    children: vec![],
+                                }),
+                                Node::Text("y".to_string()),
+                            ],
+                        }),
+                        Node::Comment("note".to_string()),
+                    ],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitespace_is_kept_where_it_matters() {
+        assert!(testing::html_diff("<pre>a  b</pre>", "<pre>a b</pre>").is_some());
+        assert!(
+            testing::html_diff(
+                "<script>if (a < b) {}</script>",
+                "<script>if (a < b) {}</script>"
+            )
+            .is_none()
+        );
+        assert!(testing::html_diff("<p>1 < 2</p>", "<p>1 &lt; 2</p>").is_none());
+    }
+
+    #[test]
+    fn test_report_points_at_first_differing_node() {
+        let report = testing::html_diff(
+            "<html><body><p>one</p><p class=\"a\">two</p></body></html>",
+            "<html><body><p>one</p><p class=\"b\">two</p></body></html>",
+        )
+        .unwrap();
+
+        assert!(report.starts_with(
+            "first difference at html[0] > body[0] > p[1]\n   left: <p class=\"a\">\n  right: <p class=\"b\">\n"
+        ));
+        assert!(report.contains("\n-     <p class=\"a\">\n+     <p class=\"b\">\n"));
+        assert!(report.contains("\n        \"two\"\n"));
+    }
+
+    #[test]
+    fn test_report_for_missing_and_changed_text() {
+        let report =
+            testing::html_diff("<ul><li>a</li></ul>", "<ul><li>a</li><li>b</li></ul>").unwrap();
+        assert!(
+            report.starts_with(
+                "first difference at ul[0] > li[1]\n   left: (nothing)\n  right: <li>\n"
+            )
+        );
+
+        let report = testing::html_diff("<p>Hello</p>", "<p>Goodbye</p>").unwrap();
+        assert!(report.starts_with(
+            "first difference at p[0] > #text[0]\n   left: \"Hello\"\n  right: \"Goodbye\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_macro_panics_with_report() {
+        let message = failure_message(|| assert_html_eq!(page(), "<div class=\"card\"></div>"));
+        assert!(message.starts_with(
+            "assertion `left == right` failed (as HTML)\nfirst difference at div[0]\n"
+        ));
+
+        let message = failure_message(|| assert_html_eq!("<b>x</b>", "<i>x</i>", "case {}", 7));
+        assert!(message.starts_with("assertion `left == right` failed (as HTML): case 7\n"));
+    }
+}
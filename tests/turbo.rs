@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod turbo_tests {
+    use markup::*;
+
+    #[test]
+    fn test_stream_actions() {
+        assert_eq!(
+            turbo::append("messages", Markup::element("p").child("a < b")).render(),
+            r#"<turbo-stream action="append" target="messages"><template><p>a &lt; b</p></template></turbo-stream>"#
+        );
+        assert_eq!(
+            turbo::stream_all(turbo::Action::Update, ".count[data-x=\"1\"]", "3").render(),
+            r#"<turbo-stream action="update" targets=".count[data-x=&quot;1&quot;]"><template>3</template></turbo-stream>"#
+        );
+        assert_eq!(
+            turbo::remove("message_1").render(),
+            r#"<turbo-stream action="remove" target="message_1"></turbo-stream>"#
+        );
+    }
+
+    #[test]
+    fn test_stream_over_sse() {
+        let update = turbo::replace("item_1", Markup::element("div").child("line 1\nline 2"));
+        assert_eq!(
+            sse::encode(update),
+            "data: <turbo-stream action=\"replace\" target=\"item_1\"><template><div>line 1\ndata: line 2</div></template></turbo-stream>\n\n"
+        );
+    }
+}